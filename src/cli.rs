@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use clap::{Arg, ArgAction, Command};
 
 pub struct CliArgs {
@@ -5,9 +6,78 @@ pub struct CliArgs {
     pub selected_mode: Option<String>,
     pub reset: bool,
     pub log: bool,
+    pub gpu: Option<String>,
+    pub list_gpus: bool,
+    pub sensors: bool,
+    pub gpu_min_freq: Option<u32>,
+    pub gpu_max_freq: Option<u32>,
+    pub gpu_boost_freq: Option<u32>,
+    pub charge_limit: Option<u8>,
+    pub charge_start: Option<u8>,
+    pub top: Option<usize>,
+    pub sort: String,
+    pub watch: bool,
+    pub format: String,
+    pub record: bool,
+    pub duration_secs: Option<u64>,
+    pub interval_ms: u64,
+    pub logs: Option<LogsCommand>,
+}
+
+/// `logs` subcommands. Those that query the telemetry store only exist with the `sqlite` feature.
+#[derive(Debug, Clone)]
+pub enum LogsCommand {
+    #[cfg(feature = "sqlite")]
+    Sessions,
+    #[cfg(feature = "sqlite")]
+    Stats {
+        session: Option<i64>, // Defaults to the newest session when no window is given
+        since: Option<u64>,
+        until: Option<u64>,
+    },
+    #[cfg(feature = "sqlite")]
+    Summary {
+        session: Option<i64>, // Same selection as `Stats`
+        since: Option<u64>,
+        until: Option<u64>,
+    },
+    #[cfg(feature = "sqlite")]
+    Compare {
+        a: i64,
+        b: i64,
+    },
+    #[cfg(feature = "sqlite")]
+    Export {
+        session: Option<i64>, // Same selection as `Stats`
+        since: Option<u64>,
+        until: Option<u64>,
+        format: String,
+        output: Option<PathBuf>,
+    },
+    Report {
+        source: String, // A session ID, or a path to a log file
+        html: PathBuf,
+    },
+    #[cfg(feature = "sqlite")]
+    Import {
+        files: Vec<PathBuf>, // Empty means every log in the logs directory
+    },
+    Prune {
+        max_files: Option<usize>, // Each limit overrides the one in retention.json
+        max_age_days: Option<u64>,
+        max_total_mb: Option<u64>,
+        compression: Option<String>,
+        dry_run: bool,
+    },
 }
 
 pub fn parse_args() -> CliArgs {
+    let logs = Command::new("logs")
+        .about("Queries recorded telemetry")
+        .subcommand_required(true);
+    #[cfg(feature = "sqlite")]
+    let logs = store_subcommands(logs);
+
     let matches = Command::new("Steam Deck Optimizer")
         .version("0.1.0")
         .author("Kyle Anthony Barrett")
@@ -18,6 +88,13 @@ pub fn parse_args() -> CliArgs {
                 .help("Displays current system resource usage")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["text", "json", "yaml", "csv"])
+                .default_value("text")
+                .help("Output format for --status"),
+        )
         .arg(
             Arg::new("mode")
                 .long("mode")
@@ -36,12 +113,321 @@ pub fn parse_args() -> CliArgs {
                 .help("Logs current system info to a file")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .help("Records samples to a session file until Ctrl-C (JSON Lines)")
+                // The recorder's Ctrl-C handler stays installed, so a following --watch couldn't be stopped
+                .conflicts_with("watch")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("duration")
+                .long("duration")
+                .value_name("S")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .requires("record")
+                .help("Stops --record after this many seconds"),
+        )
+        .arg(
+            Arg::new("gpu")
+                .long("gpu")
+                .value_name("PCI-ID|INDEX")
+                .help("Selects the GPU to report on by PCI slot, card name or index"),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64).range(100..))
+                .default_value("1000")
+                .help("Telemetry sampling interval in milliseconds"),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .value_name("MS")
+                .num_args(0..=1)
+                .value_parser(clap::value_parser!(u64).range(100..))
+                .help("Redraws a live dashboard until Ctrl-C, optionally at its own interval"),
+        )
+        .arg(
+            Arg::new("list-gpus")
+                .long("list-gpus")
+                .help("Lists every detected GPU card")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("sensors")
+                .long("sensors")
+                .help("Displays every hwmon sensor reading")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("gpu-min-freq")
+                .long("gpu-min-freq")
+                .value_name("MHZ")
+                .value_parser(clap::value_parser!(u32))
+                .help("Sets the minimum GPU clock (Intel i915/xe)"),
+        )
+        .arg(
+            Arg::new("gpu-max-freq")
+                .long("gpu-max-freq")
+                .value_name("MHZ")
+                .value_parser(clap::value_parser!(u32))
+                .help("Sets the maximum GPU clock (Intel i915/xe)"),
+        )
+        .arg(
+            Arg::new("gpu-boost-freq")
+                .long("gpu-boost-freq")
+                .value_name("MHZ")
+                .value_parser(clap::value_parser!(u32))
+                .help("Sets the boost GPU clock (Intel i915)"),
+        )
+        .arg(
+            Arg::new("charge-limit")
+                .long("charge-limit")
+                .value_name("PERCENT")
+                .value_parser(clap::value_parser!(u8).range(1..=100))
+                .help("Stops charging the battery at this percentage"),
+        )
+        .arg(
+            Arg::new("charge-start")
+                .long("charge-start")
+                .value_name("PERCENT")
+                .value_parser(clap::value_parser!(u8).range(0..=99))
+                .help("Resumes charging only below this percentage (where supported)"),
+        )
+        .arg(
+            Arg::new("top")
+                .long("top")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Lists the N busiest processes"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_parser(["cpu", "mem", "gpu"])
+                .default_value("cpu")
+                .requires("top")
+                .help("Sort order for --top"),
+        )
+        .subcommand(
+            logs
+                .subcommand(
+                    Command::new("report")
+                        .about("Writes a self-contained HTML report with charts and the session summary")
+                        .arg(
+                            Arg::new("source")
+                                .value_name("SESSION")
+                                .required(true)
+                                .help("Session ID from the store, or a session/snapshot log file"),
+                        )
+                        .arg(
+                            Arg::new("html")
+                                .long("html")
+                                .value_name("FILE")
+                                .required(true)
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("HTML file to write"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("prune")
+                        .about("Compresses old sessions and deletes logs past the retention limits")
+                        .arg(
+                            Arg::new("keep")
+                                .long("keep")
+                                .value_name("N")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("Keeps at most N log files"),
+                        )
+                        .arg(
+                            Arg::new("max-age")
+                                .long("max-age")
+                                .value_name("DAYS")
+                                .value_parser(clap::value_parser!(u64))
+                                .help("Deletes logs older than this"),
+                        )
+                        .arg(
+                            Arg::new("max-size")
+                                .long("max-size")
+                                .value_name("MB")
+                                .value_parser(clap::value_parser!(u64))
+                                .help("Deletes the oldest logs until the rest fit in this many MB"),
+                        )
+                        .arg(
+                            Arg::new("compress")
+                                .long("compress")
+                                .value_parser(["gzip", "zstd", "none"])
+                                .help("Compression for old sessions"),
+                        )
+                        .arg(
+                            Arg::new("dry-run")
+                                .long("dry-run")
+                                .help("Shows what would be deleted or compressed")
+                                .action(ArgAction::SetTrue),
+                        ),
+                ),
+        )
         .get_matches();
 
+    let logs = matches.subcommand_matches("logs").and_then(|logs| match logs.subcommand() {
+        #[cfg(feature = "sqlite")]
+        Some(("sessions", _)) => Some(LogsCommand::Sessions),
+        #[cfg(feature = "sqlite")]
+        Some(("stats", m)) => Some(LogsCommand::Stats {
+            session: m.get_one::<i64>("session").copied(),
+            since: m.get_one::<u64>("since").copied(),
+            until: m.get_one::<u64>("until").copied(),
+        }),
+        #[cfg(feature = "sqlite")]
+        Some(("summary", m)) => Some(LogsCommand::Summary {
+            session: m.get_one::<i64>("session").copied(),
+            since: m.get_one::<u64>("since").copied(),
+            until: m.get_one::<u64>("until").copied(),
+        }),
+        #[cfg(feature = "sqlite")]
+        Some(("compare", m)) => Some(LogsCommand::Compare {
+            a: m.get_one::<i64>("a").copied().unwrap_or_default(),
+            b: m.get_one::<i64>("b").copied().unwrap_or_default(),
+        }),
+        #[cfg(feature = "sqlite")]
+        Some(("export", m)) => Some(LogsCommand::Export {
+            session: m.get_one::<i64>("session").copied(),
+            since: m.get_one::<u64>("since").copied(),
+            until: m.get_one::<u64>("until").copied(),
+            format: m.get_one::<String>("format").cloned().unwrap_or_else(|| "csv".to_string()),
+            output: m.get_one::<PathBuf>("output").cloned(),
+        }),
+        Some(("report", m)) => Some(LogsCommand::Report {
+            source: m.get_one::<String>("source").cloned().unwrap_or_default(),
+            html: m.get_one::<PathBuf>("html").cloned().unwrap_or_default(),
+        }),
+        #[cfg(feature = "sqlite")]
+        Some(("import", m)) => Some(LogsCommand::Import {
+            files: m.get_many::<PathBuf>("files").map(|f| f.cloned().collect()).unwrap_or_default(),
+        }),
+        Some(("prune", m)) => Some(LogsCommand::Prune {
+            max_files: m.get_one::<usize>("keep").copied(),
+            max_age_days: m.get_one::<u64>("max-age").copied(),
+            max_total_mb: m.get_one::<u64>("max-size").copied(),
+            compression: m.get_one::<String>("compress").cloned(),
+            dry_run: m.get_flag("dry-run"),
+        }),
+        _ => None,
+    });
+
     CliArgs {
         show_status: matches.get_flag("status"),
         selected_mode: matches.get_one::<String>("mode").cloned(),
         reset: matches.get_flag("reset"),
         log: matches.get_flag("log"),
+        gpu: matches.get_one::<String>("gpu").cloned(),
+        list_gpus: matches.get_flag("list-gpus"),
+        sensors: matches.get_flag("sensors"),
+        gpu_min_freq: matches.get_one::<u32>("gpu-min-freq").copied(),
+        gpu_max_freq: matches.get_one::<u32>("gpu-max-freq").copied(),
+        gpu_boost_freq: matches.get_one::<u32>("gpu-boost-freq").copied(),
+        charge_limit: matches.get_one::<u8>("charge-limit").copied(),
+        charge_start: matches.get_one::<u8>("charge-start").copied(),
+        top: matches.get_one::<usize>("top").copied(),
+        sort: matches.get_one::<String>("sort").cloned().unwrap_or_else(|| "cpu".to_string()),
+        watch: matches.contains_id("watch"),
+        record: matches.get_flag("record"),
+        duration_secs: matches.get_one::<u64>("duration").copied(),
+        format: matches.get_one::<String>("format").cloned().unwrap_or_else(|| "text".to_string()),
+        // `--watch 500` is shorthand for `--watch --interval 500`
+        interval_ms: matches
+            .get_one::<u64>("watch")
+            .or(matches.get_one::<u64>("interval"))
+            .copied()
+            .unwrap_or(1000),
+        logs,
     }
 }
+
+/// The `logs` subcommands answered from the telemetry store.
+#[cfg(feature = "sqlite")]
+fn store_subcommands(logs: Command) -> Command {
+    logs.subcommand(Command::new("sessions").about("Lists recorded sessions"))
+        .subcommand(scope_args(Command::new("stats").about("Shows avg/min/max/p95 for a session or time window")))
+        .subcommand(
+            Command::new("import")
+                .about("Loads JSON logs and session files of any schema version into the store")
+                .arg(
+                    Arg::new("files")
+                        .value_name("FILE")
+                        .num_args(0..)
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Log files to import (default: everything in the logs directory)"),
+                ),
+        )
+        .subcommand(scope_args(
+            Command::new("summary").about("Summarizes load, power, temperature, battery drain and throttling"),
+        ))
+        .subcommand(
+            Command::new("compare")
+                .about("Shows two sessions' summaries side by side with deltas")
+                .arg(
+                    Arg::new("a")
+                        .value_name("A")
+                        .required(true)
+                        .value_parser(clap::value_parser!(i64))
+                        .help("Baseline session ID"),
+                )
+                .arg(
+                    Arg::new("b")
+                        .value_name("B")
+                        .required(true)
+                        .value_parser(clap::value_parser!(i64))
+                        .help("Session ID compared against A"),
+                ),
+        )
+        .subcommand(
+            scope_args(Command::new("export").about("Exports a session or time window as CSV or InfluxDB line protocol"))
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["csv", "influx"])
+                        .default_value("csv")
+                        .help("CSV with flattened columns, or InfluxDB line protocol"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Writes to FILE instead of stdout"),
+                ),
+        )
+}
+
+/// `--session ID` or `--since`/`--until`, shared by the `logs` subcommands that pick samples.
+#[cfg(feature = "sqlite")]
+fn scope_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("session")
+                .long("session")
+                .value_name("ID")
+                .value_parser(clap::value_parser!(i64))
+                .conflicts_with_all(["since", "until"])
+                .help("Session ID from `logs sessions` (default: the newest session)"),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .value_name("UNIX-SECS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Start of the time window"),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .value_name("UNIX-SECS")
+                .value_parser(clap::value_parser!(u64))
+                .help("End of the time window (default: now)"),
+        )
+}
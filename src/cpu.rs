@@ -0,0 +1,189 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+/// Cumulative jiffies from one `cpu`/`cpuN` line of /proc/stat.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,      // Already included in `user` by the kernel
+    pub guest_nice: u64, // Already included in `nice` by the kernel
+}
+
+impl CpuTimes {
+    fn parse(fields: &[u64]) -> Option<CpuTimes> {
+        // Kernels since 2.6.33 report at least 10 columns; older ones fewer, so missing ones are 0
+        if fields.len() < 4 {
+            return None;
+        }
+        let get = |i: usize| fields.get(i).copied().unwrap_or(0);
+        Some(CpuTimes {
+            user: get(0),
+            nice: get(1),
+            system: get(2),
+            idle: get(3),
+            iowait: get(4),
+            irq: get(5),
+            softirq: get(6),
+            steal: get(7),
+            guest: get(8),
+            guest_nice: get(9),
+        })
+    }
+
+    /// Total time, leaving out guest columns so they aren't counted twice.
+    pub fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    pub fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
+}
+
+/// The aggregate `cpu` line plus every `cpuN` line.
+#[derive(Debug, Clone, Default)]
+pub struct ProcStat {
+    pub total: CpuTimes,
+    pub cores: Vec<(u32, CpuTimes)>, // (N from cpuN, times); offline CPUs are absent
+}
+
+/// Percent of time spent in each state between two /proc/stat samples.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuUsage {
+    pub busy_percent: f64,
+    pub user_percent: f64,
+    pub nice_percent: f64,
+    pub system_percent: f64,
+    pub iowait_percent: f64,
+    pub irq_percent: f64,
+    pub softirq_percent: f64,
+    pub steal_percent: f64,
+    pub guest_percent: f64,
+}
+
+pub fn read_proc_stat() -> Option<ProcStat> {
+    parse_proc_stat(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+fn parse_proc_stat(content: &str) -> Option<ProcStat> {
+    let mut stat = ProcStat::default();
+    let mut found_total = false;
+
+    for line in content.lines() {
+        if !line.starts_with("cpu") {
+            // The cpu lines come first; stop at "intr", "ctxt", ...
+            if found_total {
+                break;
+            }
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let label = parts.next().unwrap_or_default();
+        let fields: Vec<u64> = parts.filter_map(|s| s.parse().ok()).collect();
+        let times = match CpuTimes::parse(&fields) {
+            Some(t) => t,
+            None => continue,
+        };
+
+        if label == "cpu" {
+            stat.total = times;
+            found_total = true;
+        } else if let Ok(n) = label.trim_start_matches("cpu").parse::<u32>() {
+            stat.cores.push((n, times));
+        }
+    }
+
+    if found_total { Some(stat) } else { None }
+}
+
+/// Computes the time breakdown between two samples of the same CPU.
+pub fn usage_between(prev: &CpuTimes, curr: &CpuTimes) -> CpuUsage {
+    let delta_total = curr.total().saturating_sub(prev.total());
+    if delta_total == 0 {
+        return CpuUsage::default();
+    }
+    let pct = |c: u64, p: u64| 100.0 * c.saturating_sub(p) as f64 / delta_total as f64;
+    let delta_idle = curr.idle_total().saturating_sub(prev.idle_total());
+
+    CpuUsage {
+        busy_percent: 100.0 * delta_total.saturating_sub(delta_idle) as f64 / delta_total as f64,
+        user_percent: pct(curr.user, prev.user),
+        nice_percent: pct(curr.nice, prev.nice),
+        system_percent: pct(curr.system, prev.system),
+        iowait_percent: pct(curr.iowait, prev.iowait),
+        irq_percent: pct(curr.irq, prev.irq),
+        softirq_percent: pct(curr.softirq, prev.softirq),
+        steal_percent: pct(curr.steal, prev.steal),
+        guest_percent: pct(curr.guest + curr.guest_nice, prev.guest + prev.guest_nice),
+    }
+}
+
+/// Per-core usage between two samples, matched by CPU number so hotplugged cores are skipped.
+/// Each entry keeps its CPU number, since offline cores leave gaps in the numbering.
+pub fn per_core_usage(prev: &ProcStat, curr: &ProcStat) -> Vec<(u32, CpuUsage)> {
+    curr.cores
+        .iter()
+        .map(|(n, times)| {
+            let usage = match prev.cores.iter().find(|(pn, _)| pn == n) {
+                Some((_, before)) => usage_between(before, times),
+                None => CpuUsage::default(),
+            };
+            (*n, usage)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cpu1 is offline, so the cpuN lines skip from 0 to 2
+    const STAT_WITH_GAP: &str = "cpu  300 0 100 1600 0 0 0 0 0 0\n\
+        cpu0 200 0 50 750 0 0 0 0 0 0\n\
+        cpu2 100 0 50 850 0 0 0 0 0 0\n\
+        intr 12345 0 0\n\
+        ctxt 67890\n\
+        cpu_fake 1 2 3 4\n";
+
+    #[test]
+    fn parses_cpu_numbers_across_gaps() {
+        let stat = parse_proc_stat(STAT_WITH_GAP).unwrap();
+        assert_eq!(stat.total.total(), 2000);
+        let numbers: Vec<u32> = stat.cores.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, vec![0, 2]);
+        assert_eq!(stat.cores[1].1.idle, 850);
+    }
+
+    #[test]
+    fn per_core_usage_matches_by_cpu_number() {
+        let prev = parse_proc_stat(STAT_WITH_GAP).unwrap();
+        let mut curr = prev.clone();
+        // cpu2 is fully busy for 100 jiffies, cpu0 idles
+        curr.cores[0].1.idle += 100;
+        curr.cores[1].1.user += 100;
+        // cpu1 comes back online with no previous sample
+        curr.cores.insert(1, (1, CpuTimes { user: 5, idle: 5, ..Default::default() }));
+
+        let usage = per_core_usage(&prev, &curr);
+        let numbers: Vec<u32> = usage.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, vec![0, 1, 2]);
+        assert_eq!(usage[0].1.busy_percent, 0.0);
+        assert_eq!(usage[1].1.busy_percent, 0.0);
+        assert_eq!(usage[2].1.busy_percent, 100.0);
+        assert_eq!(usage[2].1.user_percent, 100.0);
+    }
+
+    #[test]
+    fn missing_total_line_is_rejected() {
+        assert!(parse_proc_stat("cpu0 1 2 3 4\n").is_none());
+        assert!(parse_proc_stat("cpu 1 2\n").is_none());
+    }
+}
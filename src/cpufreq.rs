@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::{read_gpu_throttle_status, read_trimmed, GpuCard};
+
+/// One cpufreq policy (a group of CPUs that share a clock).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuPolicy {
+    pub policy: String,               // e.g. "policy0", or "cpu3" when read from /proc/cpuinfo
+    pub cpus: String,                 // affected_cpus, e.g. "0 1 2 3"
+    pub cur_freq_mhz: Option<u32>,
+    pub min_freq_mhz: Option<u32>,    // scaling_min_freq
+    pub max_freq_mhz: Option<u32>,    // scaling_max_freq
+    pub governor: Option<String>,
+    pub epp: Option<String>,          // energy_performance_preference (amd-pstate / intel_pstate)
+    pub driver: Option<String>,       // scaling_driver
+}
+
+/// Thermal throttling indicators. Counters are cumulative since boot; `throttled`
+/// is filled in by the sampler from the change since the previous sample.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThrottleStatus {
+    pub core_throttle_count: Option<u64>,    // Sum of cpu*/thermal_throttle/core_throttle_count (Intel)
+    pub package_throttle_count: Option<u64>, // Max of package_throttle_count across CPUs (Intel)
+    pub gpu_throttle_status: Option<u32>,    // gpu_metrics throttle_status bitmask (AMD APUs)
+    pub throttled: bool,
+}
+
+/// Reads every cpufreq policy, falling back to the per-CPU "cpu MHz" lines of /proc/cpuinfo
+/// on systems without cpufreq (VMs, some ARM boards).
+pub fn read_cpu_policies() -> Vec<CpuPolicy> {
+    let root = Path::new("/sys/devices/system/cpu/cpufreq");
+    let mut policies = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("policy") {
+                continue;
+            }
+            let dir = entry.path();
+            policies.push(CpuPolicy {
+                cpus: read_trimmed(&dir.join("affected_cpus")).unwrap_or_default(),
+                cur_freq_mhz: read_khz_as_mhz(&dir.join("scaling_cur_freq")),
+                min_freq_mhz: read_khz_as_mhz(&dir.join("scaling_min_freq")),
+                max_freq_mhz: read_khz_as_mhz(&dir.join("scaling_max_freq")),
+                governor: read_trimmed(&dir.join("scaling_governor")),
+                epp: read_trimmed(&dir.join("energy_performance_preference")),
+                driver: read_trimmed(&dir.join("scaling_driver")),
+                policy: name,
+            });
+        }
+    }
+
+    if policies.is_empty() {
+        return read_cpuinfo_mhz();
+    }
+
+    policies.sort_by_key(|p| p.policy.trim_start_matches("policy").parse::<u32>().unwrap_or(u32::MAX));
+    policies
+}
+
+fn read_cpuinfo_mhz() -> Vec<CpuPolicy> {
+    let content = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let mut policies = Vec::new();
+    let mut processor = None;
+
+    for line in content.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => continue,
+        };
+        if key == "processor" {
+            processor = Some(value.to_string());
+        } else if key == "cpu MHz" {
+            let cpu = processor.clone().unwrap_or_default();
+            policies.push(CpuPolicy {
+                policy: format!("cpu{}", cpu),
+                cpus: cpu,
+                cur_freq_mhz: value.parse::<f64>().ok().map(|mhz| mhz.round() as u32),
+                min_freq_mhz: None,
+                max_freq_mhz: None,
+                governor: None,
+                epp: None,
+                driver: None,
+            });
+        }
+    }
+    policies
+}
+
+/// Reads Intel thermal_throttle counters and, for AMD APUs, the gpu_metrics throttle bitmask.
+pub fn read_throttle_status(gpu: Option<&GpuCard>) -> ThrottleStatus {
+    let mut status = ThrottleStatus::default();
+
+    if let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_cpu = name
+                .strip_prefix("cpu")
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+            if !is_cpu {
+                continue;
+            }
+            let dir = entry.path().join("thermal_throttle");
+            if let Some(count) = read_u64(&dir.join("core_throttle_count")) {
+                status.core_throttle_count = Some(status.core_throttle_count.unwrap_or(0) + count);
+            }
+            // Every CPU in a package reports the same package counter
+            if let Some(count) = read_u64(&dir.join("package_throttle_count")) {
+                status.package_throttle_count = Some(status.package_throttle_count.unwrap_or(0).max(count));
+            }
+        }
+    }
+
+    status.gpu_throttle_status = gpu.and_then(read_gpu_throttle_status);
+    status.throttled = status.gpu_throttle_status.is_some_and(|bits| bits != 0);
+    status
+}
+
+fn read_khz_as_mhz(path: &Path) -> Option<u32> {
+    read_u64(path).map(|khz| (khz / 1000) as u32)
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse::<u64>().ok()
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use serde_json::{Map, Value};
+
+use crate::modes::active_profile;
+use crate::output::{flatten_json, write_csv};
+use crate::schema::upgrade_snapshot;
+use crate::session::hostname;
+use crate::store::{SessionRow, StoredSample};
+
+/// Measurement name for InfluxDB line protocol.
+const MEASUREMENT: &str = "steam_deck";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Influx,
+}
+
+impl ExportFormat {
+    pub fn from_str(name: &str) -> Option<ExportFormat> {
+        match name {
+            "csv" => Some(ExportFormat::Csv),
+            "influx" => Some(ExportFormat::Influx),
+            _ => None,
+        }
+    }
+}
+
+/// Tags every exported row carries, so rows from different sessions stay distinguishable.
+#[derive(Debug, Clone)]
+struct SampleTags {
+    host: Option<String>,
+    profile: String,
+    game: Option<String>,
+    session: Option<i64>,
+}
+
+/// Renders stored samples as CSV or line protocol. Host and game come from each sample's
+/// session (this machine's hostname for one-off snapshots); the profile from its governor.
+pub fn export_samples(samples: &[StoredSample], sessions: &[SessionRow], format: ExportFormat) -> Result<String, String> {
+    let by_id: HashMap<i64, &SessionRow> = sessions.iter().map(|s| (s.id, s)).collect();
+    let local_host = hostname();
+
+    let mut rows = Vec::with_capacity(samples.len());
+    for sample in samples {
+        // Stored rows go through the same upgrades as files, so every row has the current fields
+        let snapshot = upgrade_snapshot(sample.data.clone())?;
+        let session = sample.session_id.and_then(|id| by_id.get(&id));
+        let tags = SampleTags {
+            host: session.and_then(|s| s.hostname.clone()).or_else(|| local_host.clone()),
+            // Per sample, since the profile can change mid-session; old logs have no governor
+            profile: match snapshot.cpu_policies.first().and_then(|p| p.governor.as_deref()) {
+                Some(governor) => active_profile(Some(governor)),
+                None => session.map_or_else(|| active_profile(None), |s| s.profile.clone()),
+            },
+            game: session.and_then(|s| s.game.clone()),
+            session: sample.session_id,
+        };
+        let data = serde_json::to_value(&snapshot).map_err(|e| e.to_string())?;
+        rows.push((tags, sample.timestamp, data));
+    }
+
+    match format {
+        ExportFormat::Csv => {
+            let records: Vec<Value> = rows.iter().map(|(tags, _, data)| csv_record(tags, data)).collect();
+            write_csv(&records)
+        }
+        ExportFormat::Influx => Ok(rows.iter().map(|(tags, timestamp, data)| line_protocol(tags, *timestamp, data)).collect()),
+    }
+}
+
+/// Tags first, then the snapshot; `write_csv` flattens `per_core` into `per_core.0`, `per_core.1`, ...
+fn csv_record(tags: &SampleTags, data: &Value) -> Value {
+    let mut record = Map::new();
+    record.insert("host".to_string(), Value::from(tags.host.clone()));
+    record.insert("profile".to_string(), Value::from(tags.profile.clone()));
+    record.insert("game".to_string(), Value::from(tags.game.clone()));
+    record.insert("session".to_string(), Value::from(tags.session));
+    if let Value::Object(fields) = data {
+        record.extend(fields.clone());
+    }
+    Value::Object(record)
+}
+
+/// One line: `steam_deck,host=...,profile=...,game=... field=value,... <unix ns>`.
+/// Only numeric and boolean leaves become fields; strings like governor names would
+/// otherwise turn into high-cardinality fields Grafana can't plot.
+fn line_protocol(tags: &SampleTags, timestamp: u64, data: &Value) -> String {
+    let mut line = MEASUREMENT.to_string();
+    let tag_values = [
+        ("host", tags.host.clone()),
+        ("profile", Some(tags.profile.clone())),
+        ("game", tags.game.clone()),
+        ("session", tags.session.map(|id| id.to_string())),
+    ];
+    // Influx rejects empty tag values, so missing tags are left out entirely
+    for (key, value) in tag_values {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            line.push_str(&format!(",{}={}", key, escape_key(&value)));
+        }
+    }
+
+    let flat = flatten_json(data);
+    let fields: Vec<String> = flat
+        .iter()
+        .filter(|(key, _)| key.as_str() != "timestamp" && key.as_str() != "schema_version")
+        .filter_map(|(key, value)| field_value(value).map(|v| format!("{}={}", escape_key(key), v)))
+        .collect();
+    // A point needs at least one field
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    format!("{} {} {}\n", line, fields.join(","), timestamp * 1_000_000_000)
+}
+
+fn field_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        // The "i" suffix keeps integer fields integers; a float that happens to be whole
+        // stays a float, so a field's type never changes between lines
+        Value::Number(n) if n.is_f64() => n.as_f64().filter(|v| v.is_finite()).map(|v| format!("{:?}", v)),
+        Value::Number(n) => Some(format!("{}i", n)),
+        _ => None,
+    }
+}
+
+/// Tag keys, tag values and field keys escape commas, equals signs and spaces.
+fn escape_key(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tags(host: Option<&str>, game: Option<&str>) -> SampleTags {
+        SampleTags {
+            host: host.map(str::to_string),
+            profile: "balance".to_string(),
+            game: game.map(str::to_string),
+            session: Some(3),
+        }
+    }
+
+    #[test]
+    fn escapes_spaces_commas_and_equals() {
+        assert_eq!(escape_key("plain"), "plain");
+        assert_eq!(escape_key("my deck"), r"my\ deck");
+        assert_eq!(escape_key("a,b=c"), r"a\,b\=c");
+        assert_eq!(escape_key("Portal 2, Co-op=yes"), r"Portal\ 2\,\ Co-op\=yes");
+    }
+
+    #[test]
+    fn escapes_tag_values_and_field_keys_in_a_line() {
+        let data = json!({ "timestamp": 1760000000, "schema_version": 1, "disk io=busy, %": 12.5 });
+        let line = line_protocol(&tags(Some("steam deck"), Some("Half-Life 2: Episode One")), 1760000000, &data);
+        assert_eq!(
+            line,
+            "steam_deck,host=steam\\ deck,profile=balance,game=Half-Life\\ 2:\\ Episode\\ One,session=3 \
+             disk\\ io\\=busy\\,\\ %=12.5 1760000000000000000\n"
+        );
+    }
+
+    #[test]
+    fn types_fields_and_drops_missing_tags() {
+        let data = json!({
+            "uptime": 3600,
+            "memory_used_gb": 6.0,
+            "throttled": false,
+            "governor": "schedutil",
+            "gpu_temp_celsius": null,
+            "per_core": [12.0, 40.5],
+        });
+        let line = line_protocol(&tags(Some(""), None), 1, &data);
+        assert_eq!(
+            line,
+            "steam_deck,profile=balance,session=3 \
+             uptime=3600i,memory_used_gb=6.0,throttled=false,per_core.0=12.0,per_core.1=40.5 1000000000\n"
+        );
+    }
+
+    #[test]
+    fn a_sample_without_fields_writes_no_line() {
+        let data = json!({ "timestamp": 1, "schema_version": 1, "governor": "powersave" });
+        assert_eq!(line_protocol(&tags(None, None), 1, &data), "");
+    }
+}
@@ -1,10 +1,11 @@
-use std::path::{PathBuf};
+use std::path::{Path, PathBuf};
 use std::fs;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct GameInfo {
     pub name: String,
+    #[allow(dead_code)] // Not shown anywhere yet
     pub exe_path: PathBuf,
     pub cover_image: Option<PathBuf>,
     pub source: String, // "Steam" or "Prism"
@@ -38,14 +39,19 @@ pub fn discover_steam_games() -> Vec<GameInfo> {
     games
 }
 
+/// Every Steam library folder (the Steam root plus those in libraryfolders.vdf).
+pub fn steam_library_paths() -> Vec<PathBuf> {
+    find_steam_root().map(|root| parse_libraryfolders(&root)).unwrap_or_default()
+}
+
 fn find_steam_root() -> Option<PathBuf> {
     let home = std::env::var("HOME").ok()?;
     let path = PathBuf::from(format!("{}/.steam/steam", home));
     if path.exists() { Some(path) } else { None }
 }
 
-fn parse_libraryfolders(steam_root: &PathBuf) -> Vec<PathBuf> {
-    let mut paths = vec![steam_root.clone()];
+fn parse_libraryfolders(steam_root: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![steam_root.to_path_buf()];
     let config_path = steam_root.join("steamapps/libraryfolders.vdf");
     if let Ok(content) = fs::read_to_string(config_path) {
         for line in content.lines() {
@@ -62,7 +68,7 @@ fn parse_libraryfolders(steam_root: &PathBuf) -> Vec<PathBuf> {
     paths
 }
 
-fn get_cover_for_steam_game(steam_root: &PathBuf, game_name: &str) -> Option<PathBuf> {
+fn get_cover_for_steam_game(steam_root: &Path, game_name: &str) -> Option<PathBuf> {
     let grid_path = steam_root.join("userdata");
     if let Ok(users) = fs::read_dir(grid_path) {
         for user in users.flatten() {
@@ -101,6 +107,67 @@ pub fn discover_prism_games() -> Vec<GameInfo> {
     games
 }
 
+/// A game that is running right now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningGame {
+    pub name: String,
+    pub app_id: Option<u32>, // Steam app ID
+    pub pid: u32,
+}
+
+/// Finds a running Steam game. Steam starts every game under `reaper SteamLaunch AppId=<id>`,
+/// so the app ID comes from that command line and the name from the library's appmanifest.
+/// Falls back to any process whose command line points into a library's steamapps/common.
+pub fn detect_running_game() -> Option<RunningGame> {
+    let libraries = steam_library_paths();
+    let mut fallback = None;
+
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+            Ok(pid) => pid,
+            Err(_) => continue,
+        };
+        let cmdline = match fs::read(entry.path().join("cmdline")) {
+            Ok(raw) if !raw.is_empty() => raw,
+            _ => continue,
+        };
+        let args: Vec<String> = cmdline
+            .split(|b| *b == 0)
+            .map(|a| String::from_utf8_lossy(a).to_string())
+            .collect();
+
+        let is_reaper = args.first().is_some_and(|a| a.ends_with("reaper"));
+        let app_id = args.iter().find_map(|a| a.strip_prefix("AppId=")?.parse::<u32>().ok());
+        if let (true, Some(app_id)) = (is_reaper, app_id) {
+            let name = libraries
+                .iter()
+                .find_map(|lib| read_appmanifest_name(lib, app_id))
+                .unwrap_or_else(|| format!("Steam app {}", app_id));
+            return Some(RunningGame { name, app_id: Some(app_id), pid });
+        }
+
+        if fallback.is_none() {
+            fallback = args.iter().find_map(|a| {
+                let (_, rest) = a.split_once("steamapps/common/")?;
+                let dir = rest.split('/').next().filter(|d| !d.is_empty())?;
+                Some(RunningGame { name: dir.to_string(), app_id: None, pid })
+            });
+        }
+    }
+    fallback
+}
+
+fn read_appmanifest_name(library: &Path, app_id: u32) -> Option<String> {
+    let manifest = library.join(format!("steamapps/appmanifest_{}.acf", app_id));
+    let content = fs::read_to_string(manifest).ok()?;
+    // "name"		"Game Title"
+    content
+        .lines()
+        .find(|line| line.trim_start().starts_with("\"name\""))
+        .and_then(|line| line.split('"').nth(3))
+        .map(|name| name.to_string())
+}
+
 pub fn discover_all_games() -> Vec<GameInfo> {
     let mut all = discover_steam_games();
     all.extend(discover_prism_games());
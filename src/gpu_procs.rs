@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+/// Counters read from one DRM client's fdinfo at a point in time.
+#[derive(Debug, Clone, Default)]
+struct ClientCounters {
+    pdev: Option<String>,             // drm-pdev, the PCI slot of the GPU the client talks to
+    engines_ns: HashMap<String, u64>, // drm-engine-<name>, cumulative busy time in ns
+    vram_bytes: u64,                  // drm-memory-vram
+    gtt_bytes: u64,                   // drm-memory-gtt
+}
+
+/// All DRM clients found under /proc/*/fdinfo, keyed by (pid, drm-client-id).
+#[derive(Debug, Clone)]
+pub struct FdinfoSample {
+    taken_at: Instant,
+    clients: HashMap<(u32, u64), ClientCounters>,
+}
+
+/// GPU usage of a single process between two fdinfo samples.
+#[derive(Debug, Clone)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub name: String,
+    pub pdev: Option<String>,
+    pub engines: Vec<(String, f64)>, // Busy percent per engine, e.g. ("gfx", 42.0)
+    pub vram_bytes: u64,
+    pub gtt_bytes: u64,
+}
+
+impl GpuProcess {
+    pub fn engine_percent(&self, engine: &str) -> f64 {
+        self.engines
+            .iter()
+            .find(|(name, _)| name == engine)
+            .map_or(0.0, |(_, pct)| *pct)
+    }
+
+    /// The busiest engine's percentage, used for sorting.
+    pub fn busy_percent(&self) -> f64 {
+        self.engines.iter().map(|(_, pct)| *pct).fold(0.0, f64::max)
+    }
+}
+
+/// Reads every process's fdinfo and keeps the entries that belong to DRM clients.
+pub fn sample_fdinfo() -> FdinfoSample {
+    let mut clients = HashMap::new();
+
+    if let Ok(procs) = fs::read_dir("/proc") {
+        for proc_entry in procs.flatten() {
+            let pid = match proc_entry.file_name().to_string_lossy().parse::<u32>() {
+                Ok(pid) => pid,
+                Err(_) => continue,
+            };
+            let fdinfo_dir = proc_entry.path().join("fdinfo");
+            let fds = match fs::read_dir(&fdinfo_dir) {
+                Ok(fds) => fds,
+                Err(_) => continue, // Process exited or belongs to another user
+            };
+
+            for fd in fds.flatten() {
+                if let Ok(content) = fs::read_to_string(fd.path()) {
+                    add_client(&mut clients, pid, &content);
+                }
+            }
+        }
+    }
+
+    FdinfoSample {
+        taken_at: Instant::now(),
+        clients,
+    }
+}
+
+/// Records the DRM client behind one fd of `pid`, if it is one.
+fn add_client(clients: &mut HashMap<(u32, u64), ClientCounters>, pid: u32, content: &str) {
+    if let Some((client_id, counters)) = parse_fdinfo(content) {
+        // The same client shows up once per dup'd fd, so keep only the first copy
+        clients.entry((pid, client_id)).or_insert(counters);
+    }
+}
+
+/// Parses one fdinfo file. Returns None for fds that are not DRM clients.
+fn parse_fdinfo(content: &str) -> Option<(u64, ClientCounters)> {
+    let mut client_id = None;
+    let mut counters = ClientCounters::default();
+
+    for line in content.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => continue,
+        };
+
+        if key == "drm-client-id" {
+            client_id = value.parse::<u64>().ok();
+        } else if key == "drm-pdev" {
+            counters.pdev = Some(value.to_string());
+        } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+            // e.g. "drm-engine-gfx: 123456 ns"; skip drm-engine-capacity-*
+            if engine.starts_with("capacity-") {
+                continue;
+            }
+            if let Some(ns) = value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()) {
+                counters.engines_ns.insert(engine.to_string(), ns);
+            }
+        } else if key == "drm-memory-vram" {
+            counters.vram_bytes = parse_memory(value).unwrap_or(0);
+        } else if key == "drm-memory-gtt" {
+            counters.gtt_bytes = parse_memory(value).unwrap_or(0);
+        }
+    }
+
+    client_id.map(|id| (id, counters))
+}
+
+/// Parses values like "1024 KiB" or "12 MiB" into bytes.
+fn parse_memory(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let amount = parts.next()?.parse::<u64>().ok()?;
+    let multiplier = match parts.next() {
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        _ => 1,
+    };
+    Some(amount * multiplier)
+}
+
+/// Computes per-process engine busy percent and memory use between two samples.
+pub fn compute_gpu_usage(prev: &FdinfoSample, curr: &FdinfoSample) -> Vec<GpuProcess> {
+    let elapsed_ns = curr.taken_at.duration_since(prev.taken_at).as_nanos() as f64;
+    let mut by_pid: HashMap<u32, GpuProcess> = HashMap::new();
+
+    for (&(pid, client_id), counters) in &curr.clients {
+        let entry = by_pid.entry(pid).or_insert_with(|| GpuProcess {
+            pid,
+            name: process_name(pid),
+            pdev: counters.pdev.clone(),
+            engines: Vec::new(),
+            vram_bytes: 0,
+            gtt_bytes: 0,
+        });
+        entry.vram_bytes += counters.vram_bytes;
+        entry.gtt_bytes += counters.gtt_bytes;
+
+        let previous = prev.clients.get(&(pid, client_id));
+        for (engine, &ns) in &counters.engines_ns {
+            let before = previous
+                .and_then(|p| p.engines_ns.get(engine))
+                .copied()
+                .unwrap_or(ns); // New clients have no baseline yet
+            let pct = if elapsed_ns > 0.0 {
+                (ns.saturating_sub(before) as f64 / elapsed_ns * 100.0).min(100.0)
+            } else {
+                0.0
+            };
+            match entry.engines.iter_mut().find(|(name, _)| name == engine) {
+                Some((_, total)) => *total = (*total + pct).min(100.0),
+                None => entry.engines.push((engine.clone(), pct)),
+            }
+        }
+    }
+
+    let mut processes: Vec<GpuProcess> = by_pid.into_values().collect();
+    for p in &mut processes {
+        p.engines.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    processes.sort_by(|a, b| b.busy_percent().total_cmp(&a.busy_percent()));
+    processes
+}
+
+fn process_name(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| "?".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const AMDGPU: &str = "pos:\t0\nflags:\t02100002\nmnt_id:\t24\ndrm-driver:\tamdgpu\ndrm-client-id:\t12\n\
+        drm-pdev:\t0000:04:00.0\npasid:\t32771\ndrm-memory-vram:\t118356 KiB\ndrm-memory-gtt: \t2048 KiB\n\
+        drm-memory-cpu: \t0 KiB\ndrm-engine-gfx:\t1592338032 ns\ndrm-engine-compute:\t0 ns\n";
+    const I915: &str = "pos:\t0\nflags:\t02100002\ndrm-driver:\ti915\ndrm-client-id:\t7\ndrm-pdev:\t0000:00:02.0\n\
+        drm-total-system0:\t6 MiB\ndrm-engine-render:\t2557393020 ns\ndrm-engine-copy:\t0 ns\n\
+        drm-engine-capacity-video:\t2\ndrm-engine-video:\t0 ns\n";
+    const NOT_DRM: &str = "pos:\t0\nflags:\t02000002\nmnt_id:\t24\nino:\t1073\n";
+
+    /// A pid with no /proc entry, so process names come back as "?".
+    const PID: u32 = u32::MAX;
+
+    fn sample(taken_at: Instant, fds: &[(u32, String)]) -> FdinfoSample {
+        let mut clients = HashMap::new();
+        for (pid, content) in fds {
+            add_client(&mut clients, *pid, content);
+        }
+        FdinfoSample { taken_at, clients }
+    }
+
+    fn amdgpu_with_gfx(client_id: u64, gfx_ns: u64) -> String {
+        AMDGPU
+            .replace("drm-client-id:\t12", &format!("drm-client-id:\t{}", client_id))
+            .replace("1592338032 ns", &format!("{} ns", gfx_ns))
+    }
+
+    struct Case {
+        content: &'static str,
+        client_id: Option<u64>,
+        engines: &'static [(&'static str, u64)],
+        vram: u64,
+        gtt: u64,
+    }
+
+    #[test]
+    fn parses_fdinfo_samples() {
+        let cases = [
+            Case {
+                content: AMDGPU,
+                client_id: Some(12),
+                engines: &[("gfx", 1_592_338_032), ("compute", 0)],
+                vram: 118_356 * 1024,
+                gtt: 2048 * 1024,
+            },
+            Case { content: I915, client_id: Some(7), engines: &[("render", 2_557_393_020), ("copy", 0), ("video", 0)], vram: 0, gtt: 0 },
+            Case { content: NOT_DRM, client_id: None, engines: &[], vram: 0, gtt: 0 },
+        ];
+        for case in cases {
+            let parsed = parse_fdinfo(case.content);
+            assert_eq!(parsed.as_ref().map(|(id, _)| *id), case.client_id);
+            let Some((_, counters)) = parsed else { continue };
+            assert_eq!(counters.engines_ns.len(), case.engines.len(), "capacity-* lines are not engines");
+            for (engine, ns) in case.engines {
+                assert_eq!(counters.engines_ns.get(*engine), Some(ns));
+            }
+            assert_eq!(counters.vram_bytes, case.vram);
+            assert_eq!(counters.gtt_bytes, case.gtt);
+        }
+    }
+
+    #[test]
+    fn parses_memory_units() {
+        for (value, bytes) in [("512", Some(512)), ("4 KiB", Some(4096)), ("3 MiB", Some(3 << 20)), ("1 GiB", Some(1 << 30)), ("x", None)] {
+            assert_eq!(parse_memory(value), bytes, "{}", value);
+        }
+    }
+
+    #[test]
+    fn duplicate_fds_of_one_client_count_once() {
+        let start = Instant::now();
+        let prev = sample(start, &[(PID, amdgpu_with_gfx(12, 0)), (PID, amdgpu_with_gfx(12, 0))]);
+        let curr = sample(
+            start + Duration::from_secs(1),
+            &[(PID, amdgpu_with_gfx(12, 250_000_000)), (PID, amdgpu_with_gfx(12, 250_000_000))],
+        );
+        assert_eq!(curr.clients.len(), 1);
+
+        let usage = compute_gpu_usage(&prev, &curr);
+        assert_eq!(usage.len(), 1);
+        assert!((usage[0].engine_percent("gfx") - 25.0).abs() < 1e-9);
+        assert_eq!(usage[0].vram_bytes, 118_356 * 1024);
+    }
+
+    #[test]
+    fn separate_clients_of_one_process_add_up() {
+        let start = Instant::now();
+        let prev = sample(start, &[(PID, amdgpu_with_gfx(1, 0)), (PID, amdgpu_with_gfx(2, 0))]);
+        let curr = sample(
+            start + Duration::from_secs(1),
+            &[(PID, amdgpu_with_gfx(1, 300_000_000)), (PID, amdgpu_with_gfx(2, 200_000_000))],
+        );
+
+        let usage = compute_gpu_usage(&prev, &curr);
+        assert_eq!(usage.len(), 1);
+        assert!((usage[0].engine_percent("gfx") - 50.0).abs() < 1e-9);
+        assert_eq!(usage[0].vram_bytes, 2 * 118_356 * 1024);
+    }
+
+    #[test]
+    fn new_clients_start_at_zero() {
+        let start = Instant::now();
+        let prev = sample(start, &[]);
+        let curr = sample(start + Duration::from_secs(1), &[(PID, I915.to_string())]);
+
+        let usage = compute_gpu_usage(&prev, &curr);
+        assert_eq!(usage[0].name, "?");
+        assert_eq!(usage[0].busy_percent(), 0.0);
+    }
+}
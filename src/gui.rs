@@ -2,8 +2,23 @@ use eframe::egui;
 use crate::modes::{apply_mode, Mode, reset_to_default};
 use crate::logger::{log_system_info, read_latest_log};
 use crate::games::{discover_all_games, GameInfo};
+use crate::hardware::{backend_for, default_gpu_index, enumerate_gpus, GpuCard};
+use crate::intel_gpu::{hardware_freq_range, set_gpu_freq_limits, GpuFreqLimits};
+use crate::sensors::{enumerate_sensors, SensorChip};
+use crate::memory::BYTES_PER_GB;
+use crate::power::{format_minutes, set_charge_thresholds, ChargeThresholds};
+use crate::pressure::Pressure;
+use crate::processes::{read_pss_bytes, sort_processes, ProcessSort};
+use crate::sampler::{self, SamplerConfig};
+use crate::status::format_reading;
+#[cfg(feature = "sqlite")]
+use crate::output::format_timestamp;
+#[cfg(feature = "sqlite")]
+use crate::store::{Scope, SessionRow, Summary, TelemetryStore};
+use std::collections::HashMap;
 use std::sync::mpsc;
 use std::thread;
+use std::time::Instant;
 
 pub fn launch_gui() -> eframe::Result<()> {
     let options = eframe::NativeOptions::default();
@@ -18,26 +33,77 @@ struct DeckOptimizerGui {
     status_output: String,
     selected_mode: Option<Mode>,
     status_requested: bool,
-    status_result: Option<String>,
     status_receiver: Option<mpsc::Receiver<String>>,
     discovered_games: Vec<GameInfo>,
+    gpus: Vec<GpuCard>,
+    selected_gpu: Option<usize>,
+    sensors: Vec<SensorChip>,
+    gpu_freq_min: u32,
+    gpu_freq_max: u32,
+    gpu_freq_message: String,
+    charge_limit: u8,
+    charge_limit_message: String,
+    process_sort: ProcessSort,
+    pss_cache: HashMap<u32, Option<u64>>, // smaps_rollup is costly, so read once per sampler interval
+    pss_read_at: Instant,
+    #[cfg(feature = "sqlite")]
+    sessions: Vec<SessionRow>,
+    #[cfg(feature = "sqlite")]
+    session_stats: Option<(i64, Summary)>,
+    #[cfg(feature = "sqlite")]
+    sessions_message: String,
 }
 
 impl Default for DeckOptimizerGui {
     fn default() -> Self {
+        let gpus = enumerate_gpus();
+        let selected_gpu = default_gpu_index(&gpus);
+        sampler::start(SamplerConfig {
+            gpu: selected_gpu.map(|i| i.to_string()),
+            ..SamplerConfig::default()
+        });
         Self {
             status_output: String::new(),
             selected_mode: None,
             status_requested: false,
-            status_result: None,
             status_receiver: None,
             discovered_games: Vec::new(),
+            gpus,
+            selected_gpu,
+            sensors: Vec::new(),
+            gpu_freq_min: 0,
+            gpu_freq_max: 0,
+            gpu_freq_message: String::new(),
+            charge_limit: 0,
+            charge_limit_message: String::new(),
+            process_sort: ProcessSort::Cpu,
+            pss_cache: HashMap::new(),
+            pss_read_at: Instant::now(),
+            #[cfg(feature = "sqlite")]
+            sessions: Vec::new(),
+            #[cfg(feature = "sqlite")]
+            session_stats: None,
+            #[cfg(feature = "sqlite")]
+            sessions_message: String::new(),
         }
     }
 }
 
+impl DeckOptimizerGui {
+    /// Selector string for the picked card, in the same form the `--gpu` flag accepts.
+    fn gpu_selector(&self) -> Option<String> {
+        self.selected_gpu
+            .and_then(|i| self.gpus.get(i))
+            .map(|card| card.index.to_string())
+    }
+}
+
 impl eframe::App for DeckOptimizerGui {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    // Live values come from the background sampler, so reading them never blocks the UI
+    let latest = sampler::shared().latest();
+    ctx.request_repaint_after(sampler::shared().interval());
+
     egui::CentralPanel::default().show(ctx, |ui| {
         egui::ScrollArea::vertical()
             .auto_shrink([false; 2])
@@ -45,6 +111,119 @@ impl eframe::App for DeckOptimizerGui {
                 
                 ui.heading("Steam Deck Optimizer");
 
+                // --- Live Stats ---
+                ui.separator();
+                match &latest {
+                    Some(snap) => {
+                        ui.label(format!(
+                            "CPU: {} (iowait {})  RAM: {:.2} / {:.2} GB  Uptime: {} s",
+                            snap.accurate_cpu_total.map_or("N/A".to_string(), |v| format!("{:.1}%", v)),
+                            snap.cpu_breakdown.as_ref().map_or("N/A".to_string(), |b| format!("{:.1}%", b.iowait_percent)),
+                            snap.memory_used_gb,
+                            snap.memory_total_gb,
+                            snap.uptime,
+                        ));
+                        if let Some(m) = &snap.memory {
+                            let mut line = format!(
+                                "Available: {:.2} GB  Cached: {:.2} GB  Swap: {:.2} / {:.2} GB",
+                                m.available_bytes as f64 / BYTES_PER_GB,
+                                m.cached_bytes as f64 / BYTES_PER_GB,
+                                m.swap_used_bytes() as f64 / BYTES_PER_GB,
+                                m.swap_total_bytes as f64 / BYTES_PER_GB,
+                            );
+                            for z in &snap.zram {
+                                if let Some(ratio) = z.compression_ratio {
+                                    line.push_str(&format!("  {} {:.2}x", z.name, ratio));
+                                }
+                            }
+                            ui.label(line);
+                        }
+                        ui.label(format!(
+                            "GPU: {}%  {}°C  {} MHz",
+                            snap.gpu_util_percent.map_or("N/A".to_string(), |v| v.to_string()),
+                            snap.gpu_temp_celsius.map_or("N/A".to_string(), |v| format!("{:.1}", v)),
+                            snap.gpu_core_clock_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+                        ));
+                        let clocks: Vec<String> = snap
+                            .cpu_policies
+                            .iter()
+                            .map(|p| {
+                                format!(
+                                    "{} {} MHz{}",
+                                    p.policy,
+                                    p.cur_freq_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+                                    p.governor.as_ref().map_or(String::new(), |g| format!(" ({})", g))
+                                )
+                            })
+                            .collect();
+                        ui.label(format!("CPU clocks: {}", clocks.join(", ")));
+                        if let Some(l) = &snap.load_average {
+                            ui.label(format!("Load: {:.2} {:.2} {:.2}", l.load1, l.load5, l.load15));
+                        }
+                        // avg10 of the "some" line: share of the last 10 s with at least one task stalled
+                        let some_avg10 = |p: &Option<Pressure>| {
+                            p.as_ref()
+                                .and_then(|p| p.some.as_ref())
+                                .map_or("N/A".to_string(), |l| format!("{:.1}%", l.avg10))
+                        };
+                        ui.label(format!(
+                            "Pressure (10 s): CPU {}  Memory {}  IO {}",
+                            some_avg10(&snap.pressure.cpu),
+                            some_avg10(&snap.pressure.memory),
+                            some_avg10(&snap.pressure.io),
+                        ));
+                        for b in &snap.power.batteries {
+                            let estimate = match (b.time_to_empty_min, b.time_to_full_min) {
+                                (Some(min), _) => format!("  {} left", format_minutes(min)),
+                                (None, Some(min)) => format!("  {} to full", format_minutes(min)),
+                                _ => String::new(),
+                            };
+                            ui.label(format!(
+                                "Battery {}: {}% {}  {}  health {}{}",
+                                b.name,
+                                b.capacity_percent.map_or("N/A".to_string(), |v| v.to_string()),
+                                b.status.as_deref().unwrap_or("Unknown"),
+                                b.avg_power_w.map_or("N/A".to_string(), |v| format!("{:.1} W", v)),
+                                b.health_percent.map_or("N/A".to_string(), |v| format!("{:.0}%", v)),
+                                estimate,
+                            ));
+                        }
+                        if snap.throttle.throttled {
+                            ui.colored_label(egui::Color32::RED, "Thermal throttling detected");
+                        }
+                        for zone in &snap.thermal_zones {
+                            if let (Some(trip), Some(temp)) = (zone.near_trip(), zone.temp_c) {
+                                ui.colored_label(
+                                    egui::Color32::YELLOW,
+                                    format!(
+                                        "{} at {:.1}°C is near its {} trip ({:.1}°C)",
+                                        zone.zone_type, temp, trip.kind, trip.temp_c
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                    None => {
+                        ui.label("Collecting first sample...");
+                    }
+                }
+                let history = sampler::shared().history();
+                let recent: Vec<f64> = history.iter().rev().take(60).filter_map(|s| s.accurate_cpu_total).collect();
+                if !recent.is_empty() {
+                    let peak_temp = history
+                        .iter()
+                        .rev()
+                        .take(60)
+                        .filter_map(|s| s.gpu_temp_celsius)
+                        .reduce(f32::max);
+                    ui.label(format!(
+                        "Last {} samples: avg CPU {:.1}%  peak GPU temp {}",
+                        recent.len(),
+                        recent.iter().sum::<f64>() / recent.len() as f64,
+                        peak_temp.map_or("N/A".to_string(), |t| format!("{:.1}°C", t)),
+                    ));
+                }
+
                 // --- Mode Selector ---
                 ui.separator();
                 ui.label("Select Mode:");
@@ -78,6 +257,94 @@ impl eframe::App for DeckOptimizerGui {
                     ui.label(format!("Last mode applied: {:?}", mode));
                 }
 
+                // --- GPU Picker ---
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("GPU:");
+                    let current = self
+                        .selected_gpu
+                        .and_then(|i| self.gpus.get(i))
+                        .map(|c| c.describe())
+                        .unwrap_or_else(|| "No GPU detected".to_string());
+                    egui::ComboBox::from_id_source("gpu_picker")
+                        .selected_text(current)
+                        .show_ui(ui, |ui| {
+                            for (i, card) in self.gpus.iter().enumerate() {
+                                if ui.selectable_value(&mut self.selected_gpu, Some(i), card.describe()).clicked() {
+                                    // Re-read the clock sliders from the newly picked card
+                                    self.gpu_freq_min = 0;
+                                    self.gpu_freq_max = 0;
+                                    sampler::shared().set_gpu(Some(&card.index.to_string()));
+                                }
+                            }
+                        });
+                    if ui.button("Rescan").clicked() {
+                        self.gpus = enumerate_gpus();
+                        self.selected_gpu = default_gpu_index(&self.gpus);
+                        sampler::shared().set_gpu(self.gpu_selector().as_deref());
+                    }
+                });
+                if let Some(card) = self.selected_gpu.and_then(|i| self.gpus.get(i)) {
+                    ui.label(format!(
+                        "Driver: {}  PCI: {}  Backend: {}",
+                        card.driver.as_deref().unwrap_or("N/A"),
+                        card.pci_slot.as_deref().unwrap_or("N/A"),
+                        backend_for(card).name()
+                    ));
+                    let stats = latest.as_ref().map(|snap| snap.gpu.clone()).unwrap_or_default();
+
+                    // Intel cards expose min/max clock controls
+                    if let Some((rpn, rp0)) = hardware_freq_range(card) {
+                        if self.gpu_freq_min == 0 || self.gpu_freq_max == 0 {
+                            self.gpu_freq_min = stats.min_freq_mhz.unwrap_or(rpn);
+                            self.gpu_freq_max = stats.max_freq_mhz.unwrap_or(rp0);
+                        }
+                        ui.add(egui::Slider::new(&mut self.gpu_freq_min, rpn..=rp0).text("Min MHz"));
+                        ui.add(egui::Slider::new(&mut self.gpu_freq_max, rpn..=rp0).text("Max MHz"));
+                        if ui.button("Apply GPU Clocks").clicked() {
+                            let limits = GpuFreqLimits {
+                                min: Some(self.gpu_freq_min),
+                                max: Some(self.gpu_freq_max),
+                                boost: None,
+                            };
+                            self.gpu_freq_message = match set_gpu_freq_limits(card, &limits) {
+                                Ok(()) => format!("Applied {}", limits),
+                                Err(e) => format!("[Error] {}", e),
+                            };
+                        }
+                        if !self.gpu_freq_message.is_empty() {
+                            ui.label(&self.gpu_freq_message);
+                        }
+                    }
+                }
+
+                // --- Battery Charge Limit ---
+                let limited_battery = latest
+                    .as_ref()
+                    .and_then(|snap| snap.power.batteries.iter().find(|b| b.charge_end_threshold.is_some()));
+                if let Some(battery) = limited_battery {
+                    ui.separator();
+                    ui.label(format!("Battery Charge Limit ({}):", battery.name));
+                    if self.charge_limit == 0 {
+                        self.charge_limit = battery.charge_end_threshold.unwrap_or(100);
+                    }
+                    // Same range as --charge-limit, so a low threshold read from sysfs isn't clamped and written back
+                    ui.add(egui::Slider::new(&mut self.charge_limit, 1..=100).text("Stop at %"));
+                    if ui.button("Apply Charge Limit").clicked() {
+                        let thresholds = ChargeThresholds {
+                            start: None,
+                            end: Some(self.charge_limit),
+                        };
+                        self.charge_limit_message = match set_charge_thresholds(&thresholds) {
+                            Ok(name) => format!("{}: charging stops at {}%", name, self.charge_limit),
+                            Err(e) => format!("[Error] {}", e),
+                        };
+                    }
+                    if !self.charge_limit_message.is_empty() {
+                        ui.label(&self.charge_limit_message);
+                    }
+                }
+
                 // --- System Status Section ---
                 ui.separator();
                 if ui.button("Show System Status").clicked() && !self.status_requested {
@@ -126,6 +393,179 @@ impl eframe::App for DeckOptimizerGui {
                         ui.label(&self.status_output);
                     });
 
+                // --- Recorded Sessions ---
+                #[cfg(feature = "sqlite")]
+                {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.heading("Recorded Sessions");
+                        if ui.button("Refresh").clicked() {
+                            match TelemetryStore::open_default().and_then(|store| store.sessions()) {
+                                Ok(sessions) => {
+                                    self.sessions_message = format!("{} sessions", sessions.len());
+                                    self.sessions = sessions;
+                                }
+                                Err(e) => self.sessions_message = format!("[Error] {}", e),
+                            }
+                        }
+                        ui.label(&self.sessions_message);
+                    });
+                    egui::ScrollArea::vertical()
+                        .id_source("sessions_scroll")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for session in &self.sessions {
+                                let label = format!(
+                                    "#{}  {}  {}  {}  ({} samples)",
+                                    session.id,
+                                    format_timestamp(session.started_at),
+                                    session.profile,
+                                    session.game.as_deref().unwrap_or("no game"),
+                                    session.samples
+                                );
+                                let selected = self.session_stats.as_ref().is_some_and(|(id, _)| *id == session.id);
+                                if ui.selectable_label(selected, label).clicked() {
+                                    let scope = Scope::Session(session.id);
+                                    match TelemetryStore::open_default().and_then(|store| store.summary(scope)) {
+                                        Ok(stats) => self.session_stats = Some((session.id, stats)),
+                                        Err(e) => self.sessions_message = format!("[Error] {}", e),
+                                    }
+                                }
+                            }
+                        });
+                    if let Some((_, stats)) = &self.session_stats {
+                        egui::Grid::new("session_stats_grid").striped(true).show(ui, |ui| {
+                            for header in ["Metric", "Avg", "Min", "Max", "p95"] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for (metric, aggregate) in stats {
+                                ui.label(metric.label());
+                                match aggregate {
+                                    Some(a) => {
+                                        for v in [a.avg, a.min, a.max, a.p95] {
+                                            ui.label(format!("{:.1}", v));
+                                        }
+                                    }
+                                    None => {
+                                        ui.label("N/A");
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    }
+                }
+
+                // --- GPU Top ---
+                ui.separator();
+                ui.heading("GPU Processes");
+                egui::Grid::new("gpu_top_grid").striped(true).show(ui, |ui| {
+                    for header in ["PID", "Name", "Device", "GFX %", "Compute %", "VRAM MiB", "GTT MiB"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for p in latest.iter().flat_map(|snap| snap.gpu_processes.iter()) {
+                        ui.label(p.pid.to_string());
+                        ui.label(&p.name);
+                        ui.label(p.pdev.as_deref().unwrap_or("-"));
+                        ui.label(format!("{:.1}", p.engine_percent("gfx")));
+                        ui.label(format!("{:.1}", p.engine_percent("compute")));
+                        ui.label(format!("{:.1}", p.vram_bytes as f64 / 1_048_576.0));
+                        ui.label(format!("{:.1}", p.gtt_bytes as f64 / 1_048_576.0));
+                        ui.end_row();
+                    }
+                });
+
+                // --- Processes ---
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.heading("Processes");
+                    for sort in [ProcessSort::Cpu, ProcessSort::Memory, ProcessSort::Gpu] {
+                        ui.selectable_value(&mut self.process_sort, sort, sort.label());
+                    }
+                });
+                let mut processes = latest.as_ref().map(|snap| snap.processes.clone()).unwrap_or_default();
+                sort_processes(&mut processes, self.process_sort);
+                if self.pss_read_at.elapsed() >= sampler::shared().interval() {
+                    self.pss_cache.clear();
+                    self.pss_read_at = Instant::now();
+                }
+                egui::Grid::new("process_grid").striped(true).show(ui, |ui| {
+                    for header in ["PID", "Name", "CPU %", "RSS MiB", "PSS MiB", "Threads", "Nice", "GPU %", "Cgroup"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for p in processes.iter().take(15) {
+                        ui.label(p.pid.to_string());
+                        ui.label(&p.name);
+                        ui.label(format!("{:.1}", p.cpu_percent));
+                        ui.label(format!("{:.1}", p.rss_bytes as f64 / 1_048_576.0));
+                        let pss = *self.pss_cache.entry(p.pid).or_insert_with(|| read_pss_bytes(p.pid));
+                        ui.label(pss.map_or("-".to_string(), |b| format!("{:.1}", b as f64 / 1_048_576.0)));
+                        ui.label(p.threads.to_string());
+                        ui.label(p.nice.to_string());
+                        ui.label(format!("{:.1}", p.gpu_percent));
+                        // The scope/service name is the useful part of the cgroup path
+                        ui.label(p.cgroup.as_deref().and_then(|c| c.rsplit('/').next()).unwrap_or("-"));
+                        ui.end_row();
+                    }
+                });
+
+                // --- Network ---
+                ui.separator();
+                ui.heading("Network");
+                egui::Grid::new("network_grid").striped(true).show(ui, |ui| {
+                    for header in ["Interface", "State", "RX KB/s", "TX KB/s", "Signal"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for n in latest.iter().flat_map(|snap| snap.network.iter()) {
+                        ui.label(&n.name);
+                        ui.label(n.operstate.as_deref().unwrap_or("-"));
+                        ui.label(format!("{:.1}", n.rx_bytes_per_sec / 1000.0));
+                        ui.label(format!("{:.1}", n.tx_bytes_per_sec / 1000.0));
+                        ui.label(
+                            n.wifi
+                                .as_ref()
+                                .and_then(|w| w.signal_dbm)
+                                .map_or("-".to_string(), |v| format!("{:.0} dBm", v)),
+                        );
+                        ui.end_row();
+                    }
+                });
+
+                // --- Sensors ---
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.heading("Sensors");
+                    if ui.button("Refresh Sensors").clicked() {
+                        self.sensors = enumerate_sensors();
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .id_source("sensors_scroll")
+                    .max_height(220.0)
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for chip in &self.sensors {
+                            egui::CollapsingHeader::new(format!("{} ({})", chip.name, chip.hwmon))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.label(chip.path.display().to_string());
+                                    egui::Grid::new(format!("sensor_grid_{}", chip.hwmon))
+                                        .striped(true)
+                                        .show(ui, |ui| {
+                                            for reading in &chip.readings {
+                                                ui.label(reading.display_name());
+                                                ui.label(format_reading(reading));
+                                                ui.end_row();
+                                            }
+                                        });
+                                });
+                        }
+                    });
+
                 // --- Game Detection ---
                 ui.separator();
                 if ui.button("Detect Installed Games").clicked() {
@@ -1,124 +1,345 @@
 use std::fs;
 use std::io;
 use std::process::Command;
-use std::path::Path;
-use glob::glob;
-
-#[derive(Debug)]
-struct AMDGPUStats {
-    gpu_util_percent: Option<u32>,      // GPU core utilization (%)
-    vram_util_percent: Option<u32>,     // VRAM controller utilization (%)
-    core_clock_mhz: Option<u32>,        // Current core clock (MHz)
-    memory_clock_mhz: Option<u32>,      // Current memory clock (MHz)
-    temperature_c: Option<f32>,         // GPU temperature (Celsius)
-    voltage_mv: Option<u32>,            // GPU core voltage (millivolts)
-    fan_rpm: Option<u32>,               // Fan speed (RPM)
-    power_watts: Option<f32>,           // Power draw (Watts)
-    vram_used_bytes: Option<u64>,       // VRAM used (bytes)
-    vram_total_bytes: Option<u64>,      // Total VRAM (bytes)
-    gtt_used_bytes: Option<u64>,        // GTT (system memory) used (bytes)
-    gtt_total_bytes: Option<u64>,       // Total GTT size (bytes)
-    vis_vram_used_bytes: Option<u64>,   // Visible VRAM used (bytes)
-    vis_vram_total_bytes: Option<u64>,  // Total visible VRAM (bytes)
+use std::path::{Path, PathBuf};
+use crate::intel_gpu::IntelGpuBackend;
+
+/// A DRM card as exposed under /sys/class/drm (connectors like card0-DP-1 are skipped).
+#[derive(Debug, Clone)]
+pub struct GpuCard {
+    pub index: usize,               // Position in enumeration order, used by `--gpu <index>`
+    pub name: String,               // e.g. "card0"
+    pub path: PathBuf,              // e.g. /sys/class/drm/card0
+    pub vendor_id: String,          // e.g. "0x1002"
+    pub device_id: String,          // e.g. "0x163f"
+    pub pci_slot: Option<String>,   // e.g. "0000:04:00.0"
+    pub driver: Option<String>,     // e.g. "amdgpu"
+    pub boot_vga: bool,             // True for the card the firmware booted on
 }
 
-/// Collects stats for the first AMD GPU found on the system.
-fn collect_amdgpu_stats() -> io::Result<AMDGPUStats> {
-    // 1. Find an AMD GPU card under /sys/class/drm
-    let drm_path = "/sys/class/drm";
-    let mut card_path: Option<String> = None;
-    for entry in fs::read_dir(drm_path)? {
-        let entry = entry?;
-        let name = entry.file_name().into_string().unwrap_or_default();
-        // We're looking for directories named "card0", "card1", etc (not connectors like card0-DP-1)
-        if !name.starts_with("card") || name.contains('-') {
-            continue;
+impl GpuCard {
+    pub fn vendor_name(&self) -> &'static str {
+        match self.vendor_id.as_str() {
+            "0x1002" => "AMD",
+            "0x8086" => "Intel",
+            "0x10de" => "NVIDIA",
+            _ => "Unknown",
         }
-        // Check vendor ID to ensure it's an AMD GPU (vendor 0x1002)
-        let vendor_file = format!("{}/{}/device/vendor", drm_path, name);
-        if let Ok(vendor_id) = fs::read_to_string(vendor_file) {
-            if vendor_id.trim() == "0x1002" {
-                card_path = Some(format!("{}/{}", drm_path, name));
-                break;
-            }
+    }
+
+    pub fn is_amd(&self) -> bool {
+        self.vendor_id == "0x1002"
+    }
+
+    /// Matches an enumeration index, a card name ("card1") or a full or short PCI slot.
+    pub fn matches(&self, selector: &str) -> bool {
+        let selector = selector.trim();
+        if let Ok(index) = selector.parse::<usize>() {
+            return index == self.index;
+        }
+        if selector == self.name {
+            return true;
+        }
+        match &self.pci_slot {
+            Some(slot) => slot == selector || slot.ends_with(&format!(":{}", selector)),
+            None => false,
         }
     }
-    let card_path = card_path.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No AMD GPU card found"))?;
 
-    // 2. Attempt to read debugfs amdgpu_pm_info (with sudo fallback if needed)
+    /// One-line summary used by the CLI listing and the GUI picker.
+    pub fn describe(&self) -> String {
+        format!(
+            "[{}] {} {} {} ({}){}",
+            self.index,
+            self.name,
+            self.vendor_name(),
+            self.device_id,
+            self.pci_slot.as_deref().unwrap_or("no PCI slot"),
+            if self.boot_vga { " [boot]" } else { "" }
+        )
+    }
+}
+
+/// Lists every DRM card that is backed by a device with a vendor ID.
+pub fn enumerate_gpus() -> Vec<GpuCard> {
+    let drm_path = Path::new("/sys/class/drm");
+    let mut cards: Vec<(u32, GpuCard)> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(drm_path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // We're looking for directories named "card0", "card1", etc (not connectors like card0-DP-1)
+            let number = match name.strip_prefix("card").and_then(|n| n.parse::<u32>().ok()) {
+                Some(n) => n,
+                None => continue,
+            };
+
+            let path = drm_path.join(&name);
+            let device = path.join("device");
+            let vendor_id = match fs::read_to_string(device.join("vendor")) {
+                Ok(v) => v.trim().to_string(),
+                Err(_) => continue,
+            };
+            let device_id = fs::read_to_string(device.join("device"))
+                .map(|d| d.trim().to_string())
+                .unwrap_or_default();
+            let pci_slot = read_uevent_value(&device.join("uevent"), "PCI_SLOT_NAME");
+            let driver = fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+            let boot_vga = fs::read_to_string(device.join("boot_vga"))
+                .map(|v| v.trim() == "1")
+                .unwrap_or(false);
+
+            cards.push((number, GpuCard {
+                index: 0,
+                name,
+                path,
+                vendor_id,
+                device_id,
+                pci_slot,
+                driver,
+                boot_vga,
+            }));
+        }
+    }
+
+    cards.sort_by_key(|(number, _)| *number);
+    cards
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, mut card))| {
+            card.index = index;
+            card
+        })
+        .collect()
+}
+
+/// Picks the card for `selector`. Without one, prefer an AMD card, then the boot VGA card, then any card.
+pub fn select_gpu(selector: Option<&str>) -> Option<GpuCard> {
+    let cards = enumerate_gpus();
+    match selector {
+        Some(sel) => cards.into_iter().find(|c| c.matches(sel)),
+        None => default_gpu_index(&cards).map(|i| cards[i].clone()),
+    }
+}
+
+/// Index of the card used when the user hasn't picked one.
+pub fn default_gpu_index(cards: &[GpuCard]) -> Option<usize> {
+    cards
+        .iter()
+        .position(|c| c.is_amd())
+        .or_else(|| cards.iter().position(|c| c.boot_vga))
+        .or(if cards.is_empty() { None } else { Some(0) })
+}
+
+/// Contents of a sysfs or procfs file without the trailing newline. Shared by every sysfs reader.
+pub fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_uevent_value(path: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        if k == key { Some(v.trim().to_string()) } else { None }
+    })
+}
+
+/// Telemetry for one card. Each backend fills in the fields its driver exposes.
+#[derive(Debug, Default, Clone)]
+pub struct GpuStats {
+    pub gpu_util_percent: Option<u32>,      // GPU core utilization (%)
+    pub vram_util_percent: Option<u32>,     // VRAM controller utilization (%)
+    pub core_clock_mhz: Option<u32>,        // Current core clock (MHz)
+    pub memory_clock_mhz: Option<u32>,      // Current memory clock (MHz)
+    pub temperature_c: Option<f32>,         // GPU temperature (Celsius)
+    pub voltage_mv: Option<u32>,            // GPU core voltage (millivolts)
+    pub fan_rpm: Option<u32>,               // Fan speed (RPM)
+    pub power_watts: Option<f32>,           // Power draw (Watts)
+    pub vram_used_bytes: Option<u64>,       // VRAM used (bytes)
+    pub vram_total_bytes: Option<u64>,      // Total VRAM (bytes)
+    pub gtt_used_bytes: Option<u64>,        // GTT (system memory) used (bytes)
+    pub gtt_total_bytes: Option<u64>,       // Total GTT size (bytes)
+    pub vis_vram_used_bytes: Option<u64>,   // Visible VRAM used (bytes)
+    pub vis_vram_total_bytes: Option<u64>,  // Total visible VRAM (bytes)
+    pub min_freq_mhz: Option<u32>,          // Configured minimum core clock (MHz, Intel)
+    pub max_freq_mhz: Option<u32>,          // Configured maximum core clock (MHz, Intel)
+    pub boost_freq_mhz: Option<u32>,        // Configured boost core clock (MHz, i915)
+    pub rc6_residency_percent: Option<f32>, // Time spent in RC6 since the previous read (%, Intel)
+    pub energy_uj: Option<u64>,             // Cumulative energy counter (microjoules)
+}
+
+/// Collects stats for the given AMD GPU card.
+pub fn collect_amdgpu_stats(card: &GpuCard) -> io::Result<GpuStats> {
+    if !card.is_amd() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not an AMD GPU", card.name)));
+    }
+    let card_path = card.path.display().to_string();
+
+    // 1. Attempt to read debugfs amdgpu_pm_info (with sudo fallback if needed)
     let mut dbg_gpu_util = None;
     let mut dbg_vram_util = None;
     let mut dbg_temp = None;
     let mut dbg_sclk = None;
     let mut dbg_mclk = None;
-    // The debugfs entries are under /sys/kernel/debug/dri/*/amdgpu_pm_info
-    if let Ok(mut glob_iter) = glob("/sys/kernel/debug/dri/*/amdgpu_pm_info") {
-        for path in glob_iter.flatten() {
-            // We could match the correct dri<N> by comparing with our card if needed.
-            // Here, just try the first one that exists for AMDGPU.
-            let dbg_path = path.to_string_lossy().to_string();
-            // Try normal read first
-            let content = fs::read_to_string(&dbg_path).or_else(|err| {
-                if err.kind() == io::ErrorKind::PermissionDenied {
-                    // Fallback: use `sudo cat` to read, capturing output
-                    let output = Command::new("sudo")
-                        .arg("cat")
-                        .arg(&dbg_path)
-                        .output()?;
-                    if output.status.success() {
-                        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
-                    } else {
-                        Err(io::Error::new(io::ErrorKind::Other, "sudo cat failed"))
-                    }
+    // The debugfs entries are under /sys/kernel/debug/dri/<pci slot or minor>/amdgpu_pm_info.
+    // Only look at the directories belonging to this card so multi-GPU systems read the right one.
+    let minor = card.name.trim_start_matches("card");
+    let mut dbg_candidates = Vec::new();
+    if let Some(slot) = &card.pci_slot {
+        dbg_candidates.push(PathBuf::from(format!("/sys/kernel/debug/dri/{}/amdgpu_pm_info", slot)));
+    }
+    dbg_candidates.push(PathBuf::from(format!("/sys/kernel/debug/dri/{}/amdgpu_pm_info", minor)));
+    for path in dbg_candidates {
+        let dbg_path = path.to_string_lossy().to_string();
+        // Try normal read first
+        let content = fs::read_to_string(&dbg_path).or_else(|err| {
+            if err.kind() == io::ErrorKind::PermissionDenied {
+                // Fallback: use `sudo cat` to read, capturing output
+                let output = Command::new("sudo")
+                    .arg("cat")
+                    .arg(&dbg_path)
+                    .output()?;
+                if output.status.success() {
+                    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
                 } else {
-                    Err(err)
+                    Err(io::Error::other("sudo cat failed"))
                 }
-            });
-            if let Ok(text) = content {
-                for line in text.lines() {
-                    let line = line.trim();
-                    if line.starts_with("GPU Load") {
-                        // e.g., "GPU Load: 75 %"
-                        if let Some(percent_str) = line.split_whitespace().nth(2) {
-                            dbg_gpu_util = percent_str.parse::<u32>().ok();
-                        }
-                    } else if line.starts_with("MEM Load") {
-                        if let Some(percent_str) = line.split_whitespace().nth(2) {
-                            dbg_vram_util = percent_str.parse::<u32>().ok();
-                        }
-                    } else if line.starts_with("GPU Temperature") {
-                        // e.g., "GPU Temperature: 65 C"
-                        if let Some(temp_str) = line.split_whitespace().nth(2) {
-                            if let Ok(temp_val) = temp_str.parse::<u32>() {
-                                dbg_temp = Some(temp_val as f32);  // degrees C
-                            }
+            } else {
+                Err(err)
+            }
+        });
+        if let Ok(text) = content {
+            for line in text.lines() {
+                let line = line.trim();
+                if line.starts_with("GPU Load") {
+                    // e.g., "GPU Load: 75 %"
+                    if let Some(percent_str) = line.split_whitespace().nth(2) {
+                        dbg_gpu_util = percent_str.parse::<u32>().ok();
+                    }
+                } else if line.starts_with("MEM Load") {
+                    if let Some(percent_str) = line.split_whitespace().nth(2) {
+                        dbg_vram_util = percent_str.parse::<u32>().ok();
+                    }
+                } else if line.starts_with("GPU Temperature") {
+                    // e.g., "GPU Temperature: 65 C"
+                    if let Some(temp_str) = line.split_whitespace().nth(2) {
+                        if let Ok(temp_val) = temp_str.parse::<u32>() {
+                            dbg_temp = Some(temp_val as f32);  // degrees C
                         }
-                    } else if line.contains("(SCLK)") && !line.contains("PSTATE") && dbg_sclk.is_none() {
-                        // e.g., "1200 MHz (SCLK)"
-                        let parts: Vec<_> = line.split_whitespace().collect();
-                        if parts.len() >= 3 && parts[1] == "MHz" {
-                            if let Ok(freq) = parts[0].parse::<u32>() {
-                                dbg_sclk = Some(freq);
-                            }
+                    }
+                } else if line.contains("(SCLK)") && !line.contains("PSTATE") && dbg_sclk.is_none() {
+                    // e.g., "1200 MHz (SCLK)"
+                    let parts: Vec<_> = line.split_whitespace().collect();
+                    if parts.len() >= 3 && parts[1] == "MHz" {
+                        if let Ok(freq) = parts[0].parse::<u32>() {
+                            dbg_sclk = Some(freq);
                         }
-                    } else if line.contains("(MCLK)") && !line.contains("PSTATE") && dbg_mclk.is_none() {
-                        // e.g., "1000 MHz (MCLK)"
-                        let parts: Vec<_> = line.split_whitespace().collect();
-                        if parts.len() >= 3 && parts[1] == "MHz" {
-                            if let Ok(freq) = parts[0].parse::<u32>() {
-                                dbg_mclk = Some(freq);
-                            }
+                    }
+                } else if line.contains("(MCLK)") && !line.contains("PSTATE") && dbg_mclk.is_none() {
+                    // e.g., "1000 MHz (MCLK)"
+                    let parts: Vec<_> = line.split_whitespace().collect();
+                    if parts.len() >= 3 && parts[1] == "MHz" {
+                        if let Ok(freq) = parts[0].parse::<u32>() {
+                            dbg_mclk = Some(freq);
                         }
                     }
                 }
-                break; // use the first amdgpu_pm_info we successfully read
             }
+            break; // use the first amdgpu_pm_info we successfully read
         }
     }
 
-    // 3. Read sysfs entries for detailed metrics
+    // 2. Read sysfs entries for detailed metrics
     // Base device path (PCI device path) for the card
     let dev_path = format!("{}/device", card_path);
+    let mut stats = GpuStats::default();
+
+    // GPU utilization (busy percent)
+    if let Some(val) = dbg_gpu_util {
+        stats.gpu_util_percent = Some(val);
+    } else if let Ok(val) = fs::read_to_string(format!("{}/gpu_busy_percent", dev_path)) {
+        stats.gpu_util_percent = val.trim().parse::<u32>().ok();
+    }
+    // Memory controller utilization
+    if let Some(val) = dbg_vram_util {
+        stats.vram_util_percent = Some(val);
+    } else if let Ok(val) = fs::read_to_string(format!("{}/mem_busy_percent", dev_path)) {
+        stats.vram_util_percent = val.trim().parse::<u32>().ok();
+    }
+    // Clocks and temperature from debugfs; hwmon fills in whatever is missing
+    stats.core_clock_mhz = dbg_sclk;
+    stats.memory_clock_mhz = dbg_mclk;
+    stats.temperature_c = dbg_temp;
+    read_hwmon_stats(&dev_path, &mut stats);
+
+    // VRAM and GTT memory usage (bytes)
+    let mem_files = [
+        ("vram_total_bytes", "mem_info_vram_total"),
+        ("vram_used_bytes", "mem_info_vram_used"),
+        ("vis_vram_total_bytes", "mem_info_vis_vram_total"),
+        ("vis_vram_used_bytes", "mem_info_vis_vram_used"),
+        ("gtt_total_bytes", "mem_info_gtt_total"),
+        ("gtt_used_bytes", "mem_info_gtt_used"),
+    ];
+    for &(field, filename) in &mem_files {
+        let path = format!("{}/{}", dev_path, filename);
+        if let Ok(val) = fs::read_to_string(&path) {
+            if let Ok(num) = val.trim().parse::<u64>() {
+                match field {
+                    "vram_total_bytes" => stats.vram_total_bytes = Some(num),
+                    "vram_used_bytes" => stats.vram_used_bytes = Some(num),
+                    "vis_vram_total_bytes" => stats.vis_vram_total_bytes = Some(num),
+                    "vis_vram_used_bytes" => stats.vis_vram_used_bytes = Some(num),
+                    "gtt_total_bytes" => stats.gtt_total_bytes = Some(num),
+                    "gtt_used_bytes" => stats.gtt_used_bytes = Some(num),
+                    _ => { /* ignore unknown */ }
+                }
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Reads `throttle_status` from the binary gpu_metrics table that AMD APUs expose.
+/// The field sits at byte 112 in every layout `read_apu_metrics` accepts.
+pub fn read_gpu_throttle_status(card: &GpuCard) -> Option<u32> {
+    let data = read_apu_metrics(card)?;
+    let bytes = data.get(112..116)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Whole-APU (CPU + GPU + SoC) power from gpu_metrics `average_socket_power`, in watts.
+pub fn read_apu_socket_power(card: &GpuCard) -> Option<f64> {
+    let data = read_apu_metrics(card)?;
+    // u16 milliwatts at byte 44; 0xFFFF means the firmware doesn't report it
+    let raw = u16::from_le_bytes([*data.get(44)?, *data.get(45)?]);
+    if raw == u16::MAX {
+        None
+    } else {
+        Some(raw as f64 / 1000.0)
+    }
+}
+
+/// The gpu_metrics table, if it uses one of the v2.0-v2.2 APU layouts this code understands.
+fn read_apu_metrics(card: &GpuCard) -> Option<Vec<u8>> {
+    if !card.is_amd() {
+        return None;
+    }
+    let data = fs::read(card.path.join("device/gpu_metrics")).ok()?;
+    // Header: u16 structure_size, u8 format_revision, u8 content_revision
+    let (format_revision, content_revision) = (*data.get(2)?, *data.get(3)?);
+    if format_revision != 2 || content_revision > 2 {
+        return None;
+    }
+    Some(data)
+}
+
+/// Fills clocks, temperature, voltage, fan and power from the device's hwmon node.
+/// Fields that are already set are left alone so callers can prefer better sources.
+pub(crate) fn read_hwmon_stats(dev_path: &str, stats: &mut GpuStats) {
     // HWMon sensor path - assume one hwmon device under the GPU device
     let mut hwmon_path = None;
     if let Ok(entries) = fs::read_dir(format!("{}/hwmon", dev_path)) {
@@ -130,71 +351,28 @@ fn collect_amdgpu_stats() -> io::Result<AMDGPUStats> {
             }
         }
     }
-
-    // Prepare struct with all fields
-    let mut stats = AMDGPUStats {
-        gpu_util_percent: None,
-        vram_util_percent: None,
-        core_clock_mhz: None,
-        memory_clock_mhz: None,
-        temperature_c: None,
-        voltage_mv: None,
-        fan_rpm: None,
-        power_watts: None,
-        vram_used_bytes: None,
-        vram_total_bytes: None,
-        gtt_used_bytes: None,
-        gtt_total_bytes: None,
-        vis_vram_used_bytes: None,
-        vis_vram_total_bytes: None,
+    let hpath = match hwmon_path {
+        Some(p) => p,
+        None => return,
     };
 
-    // GPU utilization (busy percent)
-    if let Some(val) = dbg_gpu_util {
-        stats.gpu_util_percent = Some(val);
-    } else {
-        if let Ok(val) = fs::read_to_string(format!("{}/gpu_busy_percent", dev_path)) {
-            stats.gpu_util_percent = val.trim().parse::<u32>().ok();
-        }
-    }
-    // Memory controller utilization
-    if let Some(val) = dbg_vram_util {
-        stats.vram_util_percent = Some(val);
-    } else {
-        if let Ok(val) = fs::read_to_string(format!("{}/mem_busy_percent", dev_path)) {
-            stats.vram_util_percent = val.trim().parse::<u32>().ok();
-        }
-    }
-    // Clocks: core (MHz) and memory (MHz)
-    if let Some(freq) = dbg_sclk {
-        stats.core_clock_mhz = Some(freq);
-    }
-    if let Some(freq) = dbg_mclk {
-        stats.memory_clock_mhz = Some(freq);
-    }
+    // Clocks: hwmon reports freq1 (core) and freq2 (memory) in Hz
     if stats.core_clock_mhz.is_none() {
-        // If hwmon is available, read freq1_input (Hz)
-        if let Some(ref hpath) = hwmon_path {
-            if let Ok(val) = fs::read_to_string(format!("{}/freq1_input", hpath)) {
-                if let Ok(hz) = val.trim().parse::<u64>() {
-                    stats.core_clock_mhz = Some((hz / 1_000_000) as u32);
-                }
+        if let Ok(val) = fs::read_to_string(format!("{}/freq1_input", hpath)) {
+            if let Ok(hz) = val.trim().parse::<u64>() {
+                stats.core_clock_mhz = Some((hz / 1_000_000) as u32);
             }
         }
     }
     if stats.memory_clock_mhz.is_none() {
-        if let Some(ref hpath) = hwmon_path {
-            if let Ok(val) = fs::read_to_string(format!("{}/freq2_input", hpath)) {
-                if let Ok(hz) = val.trim().parse::<u64>() {
-                    stats.memory_clock_mhz = Some((hz / 1_000_000) as u32);
-                }
+        if let Ok(val) = fs::read_to_string(format!("{}/freq2_input", hpath)) {
+            if let Ok(hz) = val.trim().parse::<u64>() {
+                stats.memory_clock_mhz = Some((hz / 1_000_000) as u32);
             }
         }
     }
     // Temperature (Celsius)
-    if let Some(temp) = dbg_temp {
-        stats.temperature_c = Some(temp);
-    } else if let Some(ref hpath) = hwmon_path {
+    if stats.temperature_c.is_none() {
         if let Ok(val) = fs::read_to_string(format!("{}/temp1_input", hpath)) {
             if let Ok(millideg) = val.trim().parse::<u32>() {
                 stats.temperature_c = Some(millideg as f32 / 1000.0);
@@ -202,19 +380,15 @@ fn collect_amdgpu_stats() -> io::Result<AMDGPUStats> {
         }
     }
     // Voltage (mV)
-    if let Some(ref hpath) = hwmon_path {
-        if let Ok(val) = fs::read_to_string(format!("{}/in0_input", hpath)) {
-            stats.voltage_mv = val.trim().parse::<u32>().ok();
-        }
+    if let Ok(val) = fs::read_to_string(format!("{}/in0_input", hpath)) {
+        stats.voltage_mv = val.trim().parse::<u32>().ok();
     }
     // Fan speed (RPM)
-    if let Some(ref hpath) = hwmon_path {
-        if let Ok(val) = fs::read_to_string(format!("{}/fan1_input", hpath)) {
-            stats.fan_rpm = val.trim().parse::<u32>().ok();
-        }
+    if let Ok(val) = fs::read_to_string(format!("{}/fan1_input", hpath)) {
+        stats.fan_rpm = val.trim().parse::<u32>().ok();
     }
     // Power draw (Watts). Prefer averaged value if available.
-    if let Some(ref hpath) = hwmon_path {
+    if stats.power_watts.is_none() {
         let mut microwatts: Option<u64> = None;
         if let Ok(val) = fs::read_to_string(format!("{}/power1_average", hpath)) {
             microwatts = val.trim().parse::<u64>().ok();
@@ -225,43 +399,81 @@ fn collect_amdgpu_stats() -> io::Result<AMDGPUStats> {
             stats.power_watts = Some(uw as f32 / 1_000_000.0);
         }
     }
-    // VRAM and GTT memory usage (bytes)
-    let mem_files = [
-        ("vram_total_bytes", "mem_info_vram_total"),
-        ("vram_used_bytes", "mem_info_vram_used"),
-        ("vis_vram_total_bytes", "mem_info_vis_vram_total"),
-        ("vis_vram_used_bytes", "mem_info_vis_vram_used"),
-        ("gtt_total_bytes", "mem_info_gtt_total"),
-        ("gtt_used_bytes", "mem_info_gtt_used"),
-    ];
-    for &(field, filename) in &mem_files {
-        let path = format!("{}/{}", dev_path, filename);
-        if let Ok(val) = fs::read_to_string(&path) {
-            if let Ok(num) = val.trim().parse::<u64>() {
-                match field {
-                    "vram_total_bytes" => stats.vram_total_bytes = Some(num),
-                    "vram_used_bytes" => stats.vram_used_bytes = Some(num),
-                    "vis_vram_total_bytes" => stats.vis_vram_total_bytes = Some(num),
-                    "vis_vram_used_bytes" => stats.vis_vram_used_bytes = Some(num),
-                    "gtt_total_bytes" => stats.gtt_total_bytes = Some(num),
-                    "gtt_used_bytes" => stats.gtt_used_bytes = Some(num),
-                    _ => { /* ignore unknown */ }
-                }
-            }
-        }
+    // Cumulative energy (microjoules), exposed by i915/xe instead of an instantaneous power reading
+    if let Ok(val) = fs::read_to_string(format!("{}/energy1_input", hpath)) {
+        stats.energy_uj = val.trim().parse::<u64>().ok();
     }
+}
 
-    Ok(stats)
+/// A telemetry source for one family of DRM drivers.
+pub trait GpuBackend {
+    /// Short name shown in status output, e.g. "amdgpu".
+    fn name(&self) -> &'static str;
+    fn supports(&self, card: &GpuCard) -> bool;
+    fn collect(&self, card: &GpuCard) -> io::Result<GpuStats>;
+}
+
+pub struct AmdGpuBackend;
+
+impl GpuBackend for AmdGpuBackend {
+    fn name(&self) -> &'static str {
+        "amdgpu"
+    }
+
+    fn supports(&self, card: &GpuCard) -> bool {
+        card.is_amd()
+    }
+
+    fn collect(&self, card: &GpuCard) -> io::Result<GpuStats> {
+        collect_amdgpu_stats(card)
+    }
+}
+
+/// Fallback for drivers without a dedicated backend; reports whatever sysfs and hwmon expose.
+pub struct GenericDrmBackend;
+
+impl GpuBackend for GenericDrmBackend {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn supports(&self, _card: &GpuCard) -> bool {
+        true
+    }
+
+    fn collect(&self, card: &GpuCard) -> io::Result<GpuStats> {
+        let dev_path = format!("{}/device", card.path.display());
+        let mut stats = GpuStats::default();
+        if let Ok(val) = fs::read_to_string(format!("{}/gpu_busy_percent", dev_path)) {
+            stats.gpu_util_percent = val.trim().parse::<u32>().ok();
+        }
+        read_hwmon_stats(&dev_path, &mut stats);
+        Ok(stats)
+    }
 }
-pub fn get_gpu_info() -> (Option<u32>, Option<f32>, Option<u32>, Option<u32>) {
-    match collect_amdgpu_stats() {
-        Ok(stats) => (
-            stats.gpu_util_percent,
-            stats.temperature_c,
-            stats.core_clock_mhz,
-            stats.memory_clock_mhz,
-        ),
-        Err(_) => (None, None, None, None),
+
+/// Backends in priority order; the generic one accepts every card so it must stay last.
+static BACKENDS: [&(dyn GpuBackend + Sync); 3] = [&AmdGpuBackend, &IntelGpuBackend, &GenericDrmBackend];
+
+pub fn backend_for(card: &GpuCard) -> &'static dyn GpuBackend {
+    match BACKENDS.iter().find(|b| b.supports(card)) {
+        Some(backend) => *backend,
+        None => &GenericDrmBackend,
     }
 }
 
+/// Collects stats for a card using the backend that matches its vendor.
+pub fn collect_gpu_stats(card: &GpuCard) -> io::Result<GpuStats> {
+    backend_for(card).collect(card)
+}
+
+/// Collects stats for every card on the system, keyed by card.
+pub fn collect_all_gpu_stats() -> Vec<(GpuCard, io::Result<GpuStats>)> {
+    enumerate_gpus()
+        .into_iter()
+        .map(|card| {
+            let stats = collect_gpu_stats(&card);
+            (card, stats)
+        })
+        .collect()
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::hardware::{read_hwmon_stats, GpuBackend, GpuCard, GpuStats};
+use crate::permissions::privileged_write;
+
+/// Frequency control files for one Intel GT. i915 keeps them in the card directory
+/// (`gt_*_freq_mhz`), xe under `device/tile0/gt0/freq0`.
+#[derive(Debug, Clone)]
+pub struct IntelFreqFiles {
+    pub cur: PathBuf,
+    pub act: PathBuf,
+    pub min: PathBuf,
+    pub max: PathBuf,
+    pub boost: Option<PathBuf>, // xe has no boost frequency
+    pub rp0: PathBuf,           // Hardware maximum
+    pub rpn: PathBuf,           // Hardware minimum
+}
+
+pub fn is_intel(card: &GpuCard) -> bool {
+    card.vendor_id == "0x8086" || matches!(card.driver.as_deref(), Some("i915") | Some("xe"))
+}
+
+/// Locates the frequency files for the card, trying the i915 layout first and then xe.
+pub fn freq_files(card: &GpuCard) -> Option<IntelFreqFiles> {
+    let i915 = &card.path;
+    if i915.join("gt_cur_freq_mhz").exists() {
+        return Some(IntelFreqFiles {
+            cur: i915.join("gt_cur_freq_mhz"),
+            act: i915.join("gt_act_freq_mhz"),
+            min: i915.join("gt_min_freq_mhz"),
+            max: i915.join("gt_max_freq_mhz"),
+            boost: Some(i915.join("gt_boost_freq_mhz")),
+            rp0: i915.join("gt_RP0_freq_mhz"),
+            rpn: i915.join("gt_RPn_freq_mhz"),
+        });
+    }
+
+    let xe = card.path.join("device/tile0/gt0/freq0");
+    if xe.join("cur_freq").exists() {
+        return Some(IntelFreqFiles {
+            cur: xe.join("cur_freq"),
+            act: xe.join("act_freq"),
+            min: xe.join("min_freq"),
+            max: xe.join("max_freq"),
+            boost: None,
+            rp0: xe.join("rp0_freq"),
+            rpn: xe.join("rpn_freq"),
+        });
+    }
+
+    None
+}
+
+/// Requested GPU clock limits in MHz. `None` leaves that limit unchanged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuFreqLimits {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub boost: Option<u32>,
+}
+
+impl fmt::Display for GpuFreqLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: Option<u32>| v.map_or("-".to_string(), |v| format!("{} MHz", v));
+        write!(f, "min {}, max {}, boost {}", show(self.min), show(self.max), show(self.boost))
+    }
+}
+
+/// Hardware clock range (RPn, RP0) in MHz. None if either is unreadable or RPn is above RP0,
+/// which would mean a misread rather than a usable range.
+pub fn hardware_freq_range(card: &GpuCard) -> Option<(u32, u32)> {
+    let files = freq_files(card)?;
+    let rpn = read_u64(&files.rpn)? as u32;
+    let rp0 = read_u64(&files.rp0)? as u32;
+    (rpn <= rp0).then_some((rpn, rp0))
+}
+
+/// Checks the requested limits against RPn/RP0 and against each other,
+/// filling unset limits from the current values.
+fn validate_limits(files: &IntelFreqFiles, rpn: u32, rp0: u32, limits: &GpuFreqLimits) -> Result<(u32, u32), String> {
+    for (name, value) in [("min", limits.min), ("max", limits.max), ("boost", limits.boost)] {
+        if let Some(v) = value {
+            if v < rpn || v > rp0 {
+                return Err(format!("{} frequency {} MHz is outside the hardware range {}-{} MHz", name, v, rpn, rp0));
+            }
+        }
+    }
+
+    let min = limits.min.or_else(|| read_u64(&files.min).map(|v| v as u32)).unwrap_or(rpn);
+    let max = limits.max.or_else(|| read_u64(&files.max).map(|v| v as u32)).unwrap_or(rp0);
+    if min > max {
+        return Err(format!("min frequency {} MHz is above max frequency {} MHz", min, max));
+    }
+    if limits.boost.is_some() && files.boost.is_none() {
+        return Err("this driver has no boost frequency control".to_string());
+    }
+    Ok((min, max))
+}
+
+/// Writes min/max/boost GPU clock limits after validating them against the hardware range.
+pub fn set_gpu_freq_limits(card: &GpuCard, limits: &GpuFreqLimits) -> Result<(), String> {
+    let files = freq_files(card).ok_or_else(|| format!("{} has no Intel frequency controls", card.name))?;
+    let (rpn, rp0) = hardware_freq_range(card).ok_or("could not read a valid RPn/RP0 hardware range")?;
+    let (min, max) = validate_limits(&files, rpn, rp0, limits)?;
+
+    // The driver rejects a min above the current max (and vice versa), so order the writes
+    // depending on which direction the window moves.
+    let current_max = read_u64(&files.max).map(|v| v as u32).unwrap_or(rp0);
+    let mut writes: Vec<(&Path, u32)> = Vec::new();
+    if limits.min.is_some() && min > current_max {
+        writes.push((&files.max, max));
+        writes.push((&files.min, min));
+    } else {
+        if limits.min.is_some() {
+            writes.push((&files.min, min));
+        }
+        if limits.max.is_some() {
+            writes.push((&files.max, max));
+        }
+    }
+    if let (Some(boost), Some(path)) = (limits.boost, files.boost.as_deref()) {
+        writes.push((path, boost));
+    }
+
+    for (path, value) in writes {
+        if !privileged_write(path, &value.to_string()) {
+            return Err(format!("failed to write {} MHz to {}", value, path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Cumulative RC6 (idle) residency in milliseconds.
+fn read_rc6_residency_ms(card: &GpuCard) -> Option<u64> {
+    let candidates = [
+        card.path.join("power/rc6_residency_ms"),
+        card.path.join("gt/gt0/rc6_residency_ms"),
+        card.path.join("device/tile0/gt0/gtidle/idle_residency_ms"),
+    ];
+    candidates.iter().find_map(|p| read_u64(p))
+}
+
+pub fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()
+}
+
+/// Counters from the previous read of a card, so residency and power can be derived
+/// from the difference between two consecutive reads.
+struct CounterSample {
+    taken_at: Instant,
+    rc6_ms: Option<u64>,
+    energy_uj: Option<u64>,
+}
+
+static LAST_COUNTERS: Mutex<Option<HashMap<String, CounterSample>>> = Mutex::new(None);
+
+/// How long the first read of a card waits after taking its baseline counters.
+const FIRST_READ_WINDOW: Duration = Duration::from_millis(250);
+
+pub struct IntelGpuBackend;
+
+impl GpuBackend for IntelGpuBackend {
+    fn name(&self) -> &'static str {
+        "intel"
+    }
+
+    fn supports(&self, card: &GpuCard) -> bool {
+        is_intel(card)
+    }
+
+    fn collect(&self, card: &GpuCard) -> io::Result<GpuStats> {
+        let dev_path = format!("{}/device", card.path.display());
+        let mut last = LAST_COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+        let last = last.get_or_insert_with(HashMap::new);
+        if !last.contains_key(&card.name) {
+            // First read of this card: take a baseline and wait briefly, so one-shot callers
+            // (--status, --log) get load, RC6 and power instead of N/A
+            let mut baseline = GpuStats::default();
+            read_hwmon_stats(&dev_path, &mut baseline);
+            let sample = CounterSample { taken_at: Instant::now(), rc6_ms: read_rc6_residency_ms(card), energy_uj: baseline.energy_uj };
+            last.insert(card.name.clone(), sample);
+            thread::sleep(FIRST_READ_WINDOW);
+        }
+
+        let mut stats = GpuStats::default();
+
+        if let Some(files) = freq_files(card) {
+            // Prefer the actual frequency; gt_cur_freq_mhz is only what was requested
+            stats.core_clock_mhz = read_u64(&files.act)
+                .filter(|&f| f > 0)
+                .or_else(|| read_u64(&files.cur))
+                .map(|f| f as u32);
+            stats.min_freq_mhz = read_u64(&files.min).map(|f| f as u32);
+            stats.max_freq_mhz = read_u64(&files.max).map(|f| f as u32);
+            stats.boost_freq_mhz = files.boost.as_deref().and_then(read_u64).map(|f| f as u32);
+        }
+
+        read_hwmon_stats(&dev_path, &mut stats);
+
+        // Derive RC6 residency and power from the counters since the previous read
+        let now = Instant::now();
+        let rc6_ms = read_rc6_residency_ms(card);
+        if let Some(prev_sample) = last.get(&card.name) {
+            let elapsed = now.duration_since(prev_sample.taken_at).as_secs_f64();
+            if elapsed > 0.0 {
+                if let (Some(prev), Some(curr)) = (prev_sample.rc6_ms, rc6_ms) {
+                    let pct = (curr.saturating_sub(prev) as f64 / (elapsed * 1000.0) * 100.0).min(100.0);
+                    stats.rc6_residency_percent = Some(pct as f32);
+                    // The GT is busy whenever it isn't in RC6
+                    stats.gpu_util_percent = Some((100.0 - pct).round() as u32);
+                }
+                if stats.power_watts.is_none() {
+                    if let (Some(prev), Some(curr)) = (prev_sample.energy_uj, stats.energy_uj) {
+                        stats.power_watts = Some((curr.saturating_sub(prev) as f64 / 1_000_000.0 / elapsed) as f32);
+                    }
+                }
+            }
+        }
+        last.insert(card.name.clone(), CounterSample { taken_at: now, rc6_ms, energy_uj: stats.energy_uj });
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,486 @@
+use crate::cli::LogsCommand;
+use crate::paths::ensure_logs_dir;
+#[cfg(feature = "sqlite")]
+use crate::paths::{log_kind, prepare_logs_dir, LogKind};
+use crate::retention::{apply_retention, load_policy, print_report, Compression};
+use crate::report::{render_html, ReportMeta};
+use crate::sampler::Snapshot;
+use crate::schema::{read_log_file, LogFile};
+use crate::summary::{estimate_interval, print_summary, summarize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+#[cfg(feature = "sqlite")]
+use crate::export::{export_samples, ExportFormat};
+#[cfg(feature = "sqlite")]
+use crate::output::format_timestamp;
+#[cfg(feature = "sqlite")]
+use crate::power::format_minutes;
+#[cfg(feature = "sqlite")]
+use crate::schema::upgrade_snapshot;
+#[cfg(feature = "sqlite")]
+use crate::store::{Scope, SessionRow, TelemetryStore};
+#[cfg(feature = "sqlite")]
+use crate::summary::{format_value, SessionSummary};
+#[cfg(feature = "sqlite")]
+use std::path::PathBuf;
+
+/// Runs a `logs` subcommand.
+pub fn run_logs(command: &LogsCommand) {
+    match command {
+        LogsCommand::Prune { max_files, max_age_days, max_total_mb, compression, dry_run } => {
+            let mut policy = load_policy();
+            // Flags override the config file limit by limit
+            policy.max_files = max_files.or(policy.max_files);
+            policy.max_age_days = max_age_days.or(policy.max_age_days);
+            policy.max_total_mb = max_total_mb.or(policy.max_total_mb);
+            if let Some(compression) = compression.as_deref().and_then(Compression::from_str) {
+                policy.compression = compression;
+            }
+            match ensure_logs_dir().and_then(|dir| apply_retention(&dir, &policy, *dry_run)) {
+                Ok(report) => print_report(&report, *dry_run),
+                Err(e) => eprintln!("[Logs] Prune failed: {}", e),
+            }
+        }
+        LogsCommand::Report { source, html } => write_report(source, html),
+        // Every other subcommand only exists with the store
+        #[cfg(feature = "sqlite")]
+        _ => run_store_command(command),
+    }
+}
+
+/// Writes the HTML report for a stored session (numeric source) or a log file.
+fn write_report(source: &str, output: &Path) {
+    // A file that happens to have a numeric name still counts as a file
+    let loaded = match source.parse::<i64>() {
+        Ok(id) if !Path::new(source).exists() => load_stored_report(id),
+        _ => load_file_report(Path::new(source)),
+    };
+    let (meta, samples) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("[Logs] {}", e);
+            return;
+        }
+    };
+    if samples.is_empty() {
+        eprintln!("[Logs] No samples to report");
+        return;
+    }
+    match fs::write(output, render_html(&meta, &samples)) {
+        Ok(()) => println!("[Logs] Wrote a report of {} samples to {}", samples.len(), output.display()),
+        Err(e) => eprintln!("[Logs] Could not write {}: {}", output.display(), e),
+    }
+}
+
+fn load_file_report(path: &Path) -> Result<(ReportMeta, Vec<Snapshot>), String> {
+    let title = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    Ok(match read_log_file(path)? {
+        LogFile::Session(log) => {
+            if log.skipped_lines > 0 {
+                eprintln!("[Logs] {}: skipped {} unreadable lines", path.display(), log.skipped_lines);
+            }
+            (ReportMeta::from_session_log(title, &log), log.samples)
+        }
+        LogFile::Snapshot(snapshot) => {
+            let samples = vec![*snapshot];
+            (ReportMeta::from_samples(title, &samples), samples)
+        }
+    })
+}
+
+#[cfg(feature = "sqlite")]
+fn load_stored_report(id: i64) -> Result<(ReportMeta, Vec<Snapshot>), String> {
+    let store = TelemetryStore::open_default().map_err(|e| format!("Could not open the telemetry store: {}", e))?;
+    let session = store
+        .session(id)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .ok_or_else(|| format!("No session with ID {}", id))?;
+    let samples = load_snapshots(&store, Scope::Session(id)).map_err(|e| format!("Query failed: {}", e))?;
+    Ok((ReportMeta::from_session_row(&session), samples))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn load_stored_report(_id: i64) -> Result<(ReportMeta, Vec<Snapshot>), String> {
+    Err("Built without the `sqlite` feature; pass a session file instead of an ID".to_string())
+}
+
+/// Subcommands answered from the telemetry store.
+#[cfg(feature = "sqlite")]
+fn run_store_command(command: &LogsCommand) {
+    let store = match TelemetryStore::open_default() {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Logs] Could not open the telemetry store: {}", e);
+            return;
+        }
+    };
+    let result = match *command {
+        LogsCommand::Sessions => print_sessions(&store),
+        LogsCommand::Stats { session, since, until } => print_stats(&store, session, since, until),
+        LogsCommand::Export { session, since, until, ref format, ref output } => {
+            let format = ExportFormat::from_str(format).unwrap_or(ExportFormat::Csv);
+            export_logs(&store, session, since, until, format, output.as_deref())
+        }
+        LogsCommand::Summary { session, since, until } => print_scope_summary(&store, session, since, until),
+        LogsCommand::Compare { a, b } => compare_sessions(&store, a, b),
+        LogsCommand::Import { ref files } => import_logs(&store, files),
+        LogsCommand::Prune { .. } | LogsCommand::Report { .. } => Ok(()),
+    };
+    if let Err(e) = result {
+        eprintln!("[Logs] Query failed: {}", e);
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn print_sessions(store: &TelemetryStore) -> rusqlite::Result<()> {
+    let sessions = store.sessions()?;
+    if sessions.is_empty() {
+        println!("[Logs] No recorded sessions yet. Start one with --record.");
+        return Ok(());
+    }
+
+    println!(
+        "{:>4}  {:<19}  {:>8}  {:>7}  {:<14}  Game",
+        "ID", "Started (UTC)", "Length", "Samples", "Profile"
+    );
+    for s in &sessions {
+        println!(
+            "{:>4}  {:<19}  {:>8}  {:>7}  {:<14}  {}",
+            s.id,
+            format_timestamp(s.started_at),
+            session_length(s),
+            s.samples,
+            s.profile,
+            s.game.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn session_length(session: &SessionRow) -> String {
+    match session.ended_at {
+        Some(end) => format_minutes(end.saturating_sub(session.started_at) as f64 / 60.0),
+        None => "open".to_string(),
+    }
+}
+
+/// The explicit session or window, else the newest session. None (after saying why) if
+/// nothing has been recorded yet.
+#[cfg(feature = "sqlite")]
+fn resolve_scope(
+    store: &TelemetryStore,
+    session: Option<i64>,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> rusqlite::Result<Option<Scope>> {
+    Ok(match (session, since, until) {
+        (Some(id), _, _) => Some(Scope::Session(id)),
+        (None, None, None) => match store.sessions()?.first() {
+            Some(newest) => Some(Scope::Session(newest.id)),
+            None => {
+                eprintln!("[Logs] No recorded sessions yet. Start one with --record.");
+                None
+            }
+        },
+        (None, since, until) => Some(Scope::Window {
+            from: since.unwrap_or(0),
+            to: until.unwrap_or(u64::MAX),
+        }),
+    })
+}
+
+#[cfg(feature = "sqlite")]
+fn print_stats(
+    store: &TelemetryStore,
+    session: Option<i64>,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> rusqlite::Result<()> {
+    let scope = match resolve_scope(store, session, since, until)? {
+        Some(scope) => scope,
+        None => return Ok(()),
+    };
+
+    match scope {
+        Scope::Session(id) => match store.session(id)? {
+            Some(s) => println!(
+                "Session {} — {} — {} — {} ({} samples)",
+                s.id,
+                format_timestamp(s.started_at),
+                session_length(&s),
+                s.game.as_deref().unwrap_or("no game"),
+                s.samples,
+            ),
+            None => {
+                eprintln!("[Logs] No session with ID {}", id);
+                return Ok(());
+            }
+        },
+        Scope::Window { from, to } => println!(
+            "Samples from {} to {}",
+            format_timestamp(from),
+            if to == u64::MAX { "now".to_string() } else { format_timestamp(to) }
+        ),
+    }
+
+    println!("{:<10}  {:>8}  {:>8}  {:>8}  {:>8}", "Metric", "Avg", "Min", "Max", "p95");
+    for (metric, aggregate) in store.summary(scope)? {
+        match aggregate {
+            Some(a) => println!(
+                "{:<10}  {:>8.1}  {:>8.1}  {:>8.1}  {:>8.1}",
+                metric.label(), a.avg, a.min, a.max, a.p95
+            ),
+            None => println!("{:<10}  {:>8}", metric.label(), "N/A"),
+        }
+    }
+    Ok(())
+}
+
+/// Summary printed when `--record` finishes, read back from the file it just wrote.
+pub fn print_session_summary(path: &Path) {
+    let log = match read_log_file(path) {
+        Ok(LogFile::Session(log)) => log,
+        Ok(LogFile::Snapshot(_)) => return,
+        Err(e) => {
+            eprintln!("[Record] Could not summarize the session: {}", e);
+            return;
+        }
+    };
+    let interval = match &log.info {
+        Some(info) => Duration::from_millis(info.interval_ms),
+        None => estimate_interval(&log.samples),
+    };
+    if log.samples.is_empty() {
+        return;
+    }
+    println!("[Record] Session summary:");
+    print_summary(&summarize(&log.samples, interval));
+}
+
+/// Stored samples for a scope, upgraded to the current schema.
+#[cfg(feature = "sqlite")]
+fn load_snapshots(store: &TelemetryStore, scope: Scope) -> rusqlite::Result<Vec<Snapshot>> {
+    let mut snapshots = Vec::new();
+    for sample in store.samples(scope)? {
+        match upgrade_snapshot(sample.data) {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => eprintln!("[Logs] Skipping sample at {}: {}", sample.timestamp, e),
+        }
+    }
+    Ok(snapshots)
+}
+
+/// A session's summary, using its recorded interval. None (after saying why) if it doesn't exist.
+#[cfg(feature = "sqlite")]
+fn session_summary(store: &TelemetryStore, id: i64) -> rusqlite::Result<Option<(SessionRow, SessionSummary)>> {
+    let session = match store.session(id)? {
+        Some(session) => session,
+        None => {
+            eprintln!("[Logs] No session with ID {}", id);
+            return Ok(None);
+        }
+    };
+    let snapshots = load_snapshots(store, Scope::Session(id))?;
+    let summary = summarize(&snapshots, Duration::from_millis(session.interval_ms));
+    Ok(Some((session, summary)))
+}
+
+#[cfg(feature = "sqlite")]
+fn print_scope_summary(
+    store: &TelemetryStore,
+    session: Option<i64>,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> rusqlite::Result<()> {
+    let summary = match resolve_scope(store, session, since, until)? {
+        Some(Scope::Session(id)) => match session_summary(store, id)? {
+            Some((session, summary)) => {
+                println!(
+                    "Session {} — {} — {} — {}",
+                    session.id,
+                    format_timestamp(session.started_at),
+                    session.profile,
+                    session.game.as_deref().unwrap_or("no game"),
+                );
+                summary
+            }
+            None => return Ok(()),
+        },
+        Some(scope @ Scope::Window { from, to }) => {
+            println!(
+                "Samples from {} to {}",
+                format_timestamp(from),
+                if to == u64::MAX { "now".to_string() } else { format_timestamp(to) }
+            );
+            // A window can span sessions, so the interval is estimated from the samples
+            let snapshots = load_snapshots(store, scope)?;
+            summarize(&snapshots, estimate_interval(&snapshots))
+        }
+        None => return Ok(()),
+    };
+
+    if summary.samples == 0 {
+        eprintln!("[Logs] No samples to summarize");
+        return Ok(());
+    }
+    print_summary(&summary);
+    Ok(())
+}
+
+/// Two sessions side by side; the delta is B minus A, marked better or worse for the cost metrics.
+#[cfg(feature = "sqlite")]
+fn compare_sessions(store: &TelemetryStore, a: i64, b: i64) -> rusqlite::Result<()> {
+    let (Some((session_a, summary_a)), Some((session_b, summary_b))) = (session_summary(store, a)?, session_summary(store, b)?)
+    else {
+        return Ok(());
+    };
+
+    for (tag, session) in [("A", &session_a), ("B", &session_b)] {
+        println!(
+            "{}: session {} — {} — {} — {}",
+            tag,
+            session.id,
+            format_timestamp(session.started_at),
+            session.profile,
+            session.game.as_deref().unwrap_or("no game"),
+        );
+    }
+    if session_a.game != session_b.game {
+        println!("[Logs] Note: the sessions ran different games, so differences aren't only the profile");
+    }
+
+    println!("{:<18}  {:>9}  {:>9}  {:>22}", "Metric", "A", "B", "Delta");
+    let (minutes_a, minutes_b) = (summary_a.duration_minutes(), summary_b.duration_minutes());
+    println!(
+        "{:<18}  {:>9.1}  {:>9.1}  {:>22}",
+        "Duration (min)",
+        minutes_a,
+        minutes_b,
+        format!("{:+.1}", minutes_b - minutes_a)
+    );
+    for (row_a, row_b) in summary_a.rows().iter().zip(summary_b.rows()) {
+        let delta = match (row_a.value, row_b.value) {
+            (Some(va), Some(vb)) => {
+                let diff = vb - va;
+                let percent = if va.abs() > f64::EPSILON {
+                    format!(" ({:+.1}%)", 100.0 * diff / va)
+                } else {
+                    String::new()
+                };
+                // Load rows get no verdict, and rounding noise isn't called better or worse
+                let verdict = if !row_a.rated || diff.abs() < 0.05 {
+                    ""
+                } else if diff < 0.0 {
+                    " better"
+                } else {
+                    " worse"
+                };
+                format!("{:+.1}{}{}", diff, percent, verdict)
+            }
+            _ => "N/A".to_string(),
+        };
+        println!("{:<18}  {:>9}  {:>9}  {:>22}", row_a.label, format_value(row_a.value), format_value(row_b.value), delta);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn import_logs(store: &TelemetryStore, files: &[PathBuf]) -> rusqlite::Result<()> {
+    let files = if files.is_empty() { logs_dir_files() } else { files.to_vec() };
+    let (mut sessions, mut snapshots, mut existing) = (0, 0, 0);
+
+    for path in &files {
+        match read_log_file(path) {
+            Ok(LogFile::Snapshot(snapshot)) => {
+                if store.import_snapshot(&snapshot)? {
+                    snapshots += 1;
+                } else {
+                    existing += 1;
+                }
+            }
+            Ok(LogFile::Session(log)) => {
+                let info = match &log.info {
+                    Some(info) => info,
+                    None => {
+                        eprintln!("[Logs] {}: no session header, skipped", path.display());
+                        continue;
+                    }
+                };
+                if log.skipped_lines > 0 {
+                    eprintln!("[Logs] {}: skipped {} unreadable lines", path.display(), log.skipped_lines);
+                }
+                match store.import_session(info, &log)? {
+                    Some(_) => sessions += 1,
+                    None => existing += 1,
+                }
+            }
+            Err(e) => eprintln!("[Logs] {}", e),
+        }
+    }
+
+    println!(
+        "[Logs] Imported {} sessions and {} snapshots ({} already stored)",
+        sessions, snapshots, existing
+    );
+    Ok(())
+}
+
+/// Snapshot and session files in the logs directory, oldest first. Importing writes to the
+/// store, so this counts as a write path and picks up files left in `./logs` too.
+#[cfg(feature = "sqlite")]
+fn logs_dir_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = prepare_logs_dir()
+        .and_then(fs::read_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    let name = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    matches!(log_kind(&name), Some(LogKind::Snapshot | LogKind::Session))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+#[cfg(feature = "sqlite")]
+fn export_logs(
+    store: &TelemetryStore,
+    session: Option<i64>,
+    since: Option<u64>,
+    until: Option<u64>,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> rusqlite::Result<()> {
+    let scope = match resolve_scope(store, session, since, until)? {
+        Some(scope) => scope,
+        None => return Ok(()),
+    };
+    let samples = store.samples(scope)?;
+    if samples.is_empty() {
+        eprintln!("[Logs] No samples to export");
+        return Ok(());
+    }
+
+    let rendered = match export_samples(&samples, &store.sessions()?, format) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            eprintln!("[Logs] Export failed: {}", e);
+            return Ok(());
+        }
+    };
+    match output {
+        // Status goes to stderr so stdout can be piped straight into a file or influx
+        Some(path) => match fs::write(path, rendered) {
+            Ok(()) => eprintln!("[Logs] Exported {} samples to {}", samples.len(), path.display()),
+            Err(e) => eprintln!("[Logs] Could not write {}: {}", path.display(), e),
+        },
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
@@ -1,17 +1,54 @@
 mod cli;
+mod cpu;
+mod cpufreq;
+mod pressure;
+mod memory;
+mod power;
+mod rapl;
+mod thermal;
+mod storage;
+mod network;
+mod processes;
+mod watch;
+mod output;
+mod session;
+#[cfg(feature = "sqlite")]
+mod store;
+#[cfg(feature = "sqlite")]
+mod export;
+mod logs;
+mod paths;
+mod retention;
+mod schema;
+mod summary;
+mod report;
 mod status;
 mod modes;
 mod logger;
 mod permissions;
 mod hardware;
+mod intel_gpu;
 mod gui;
 mod games;
+mod sampler;
+mod sensors;
+mod gpu_procs;
 use cli::parse_args;
 use permissions::ensure_gpu_permissions;
 use modes::{apply_mode, Mode, reset_to_default};
-use status::print_system_status;
+use status::{print_gpu_list, print_sensors, print_structured_status, print_system_status, print_top_processes};
+use output::OutputFormat;
+use session::record_session;
+use logs::{print_session_summary, run_logs};
+use processes::ProcessSort;
+use watch::run_watch;
 use logger::log_system_info;
 use gui::launch_gui;
+use hardware::select_gpu;
+use intel_gpu::{set_gpu_freq_limits, GpuFreqLimits};
+use power::{set_charge_thresholds, ChargeThresholds};
+use sampler::SamplerConfig;
+use std::time::Duration;
 
 fn main() {
     // Ensure proper GPU access and udev setup before anything else
@@ -21,7 +58,10 @@ fn main() {
     let args = parse_args();
 
     // If no flags are passed, launch the GUI instead
-    let no_flags = !args.show_status && args.selected_mode.is_none() && !args.reset && !args.log;
+    let no_flags = !args.show_status && args.selected_mode.is_none() && !args.reset && !args.log
+        && !args.list_gpus && !args.sensors && args.gpu_min_freq.is_none() && args.gpu_max_freq.is_none()
+        && args.gpu_boost_freq.is_none() && args.charge_limit.is_none() && args.charge_start.is_none() && args.top.is_none() && !args.watch && !args.record
+        && args.logs.is_none();
     if no_flags {
         if let Err(e) = launch_gui() {
             eprintln!("[GUI] Failed to launch: {}", e);
@@ -29,9 +69,38 @@ fn main() {
         return;
     }
 
+    // Stored telemetry needs no live sampling
+    if let Some(command) = &args.logs {
+        run_logs(command);
+        return;
+    }
+
+    // Start the shared telemetry sampler so status and logging read instantly
+    sampler::start(SamplerConfig {
+        interval: Duration::from_millis(args.interval_ms),
+        gpu: args.gpu.clone(),
+        ..SamplerConfig::default()
+    });
+
     // CLI logic
+    if args.list_gpus {
+        print_gpu_list();
+    }
+
+    if args.sensors {
+        print_sensors();
+    }
+
     if args.show_status {
-        print_system_status();
+        match OutputFormat::from_str(&args.format).unwrap_or(OutputFormat::Text) {
+            OutputFormat::Text => print_system_status(),
+            format => print_structured_status(format),
+        }
+    }
+
+    if let Some(count) = args.top {
+        let sort = ProcessSort::from_str(&args.sort).unwrap_or(ProcessSort::Cpu);
+        print_top_processes(count, sort);
     }
 
     if let Some(mode_str) = args.selected_mode {
@@ -41,6 +110,32 @@ fn main() {
         }
     }
 
+    let freq_limits = GpuFreqLimits {
+        min: args.gpu_min_freq,
+        max: args.gpu_max_freq,
+        boost: args.gpu_boost_freq,
+    };
+    if freq_limits != GpuFreqLimits::default() {
+        match select_gpu(args.gpu.as_deref()) {
+            Some(card) => match set_gpu_freq_limits(&card, &freq_limits) {
+                Ok(()) => println!("[GPU] {} clocks set to {}", card.name, freq_limits),
+                Err(e) => eprintln!("[GPU] {}: {}", card.name, e),
+            },
+            None => eprintln!("[GPU] No matching GPU card found"),
+        }
+    }
+
+    let charge = ChargeThresholds {
+        start: args.charge_start,
+        end: args.charge_limit,
+    };
+    if charge != ChargeThresholds::default() {
+        match set_charge_thresholds(&charge) {
+            Ok(battery) => println!("[Battery] {} charge thresholds set ({})", battery, charge),
+            Err(e) => eprintln!("[Battery] {}", e),
+        }
+    }
+
     if args.reset {
         reset_to_default();
     }
@@ -48,4 +143,19 @@ fn main() {
     if args.log {
         log_system_info();
     }
+
+    // Record and watch run until interrupted, so they go after every one-shot action (clap keeps them exclusive)
+    if args.record {
+        match record_session(args.duration_secs.map(Duration::from_secs)) {
+            Ok((path, samples)) => {
+                println!("[Record] Saved {} samples to {}", samples, path.display());
+                print_session_summary(&path);
+            }
+            Err(e) => eprintln!("[Record] Session failed: {}", e),
+        }
+    }
+
+    if args.watch {
+        run_watch();
+    }
 }
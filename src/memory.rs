@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+/// Bytes in one GB. The log schema and CLI report decimal gigabytes.
+pub const BYTES_PER_GB: f64 = 1_000_000_000.0;
+
+/// Selected /proc/meminfo fields, converted from kB to bytes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub available_bytes: u64, // MemAvailable: what can be allocated without swapping
+    pub buffers_bytes: u64,
+    pub cached_bytes: u64,
+    pub shmem_bytes: u64,     // tmpfs and shared memory; counted inside `cached`
+    pub dirty_bytes: u64,     // Waiting to be written back to disk
+    pub writeback_bytes: u64,
+    pub swap_total_bytes: u64,
+    pub swap_free_bytes: u64,
+    pub swap_cached_bytes: u64,
+}
+
+impl MemoryStats {
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.available_bytes)
+    }
+
+    pub fn swap_used_bytes(&self) -> u64 {
+        self.swap_total_bytes.saturating_sub(self.swap_free_bytes)
+    }
+}
+
+/// One zram device, from /sys/block/zramN/mm_stat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZramDevice {
+    pub name: String,
+    pub algorithm: Option<String>,    // Selected entry of comp_algorithm
+    pub disksize_bytes: u64,
+    pub orig_data_bytes: u64,         // Uncompressed size of the stored data
+    pub compr_data_bytes: u64,        // Compressed size of the stored data
+    pub mem_used_bytes: u64,          // RAM used, including allocator overhead
+    pub mem_limit_bytes: u64,         // 0 = unlimited
+    pub mem_used_max_bytes: u64,
+    pub compression_ratio: Option<f64>, // orig / compr; None while the device is empty
+}
+
+pub fn read_meminfo() -> Option<MemoryStats> {
+    let content = fs::read_to_string("/proc/meminfo").ok()?;
+    let mut stats = MemoryStats::default();
+
+    // e.g. "MemAvailable:   12345678 kB"
+    for line in content.lines() {
+        let (key, rest) = match line.split_once(':') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        let kb: u64 = match rest.split_whitespace().next().and_then(|v| v.parse().ok()) {
+            Some(v) => v,
+            None => continue,
+        };
+        let bytes = kb * 1024;
+        match key {
+            "MemTotal" => stats.total_bytes = bytes,
+            "MemFree" => stats.free_bytes = bytes,
+            "MemAvailable" => stats.available_bytes = bytes,
+            "Buffers" => stats.buffers_bytes = bytes,
+            "Cached" => stats.cached_bytes = bytes,
+            "Shmem" => stats.shmem_bytes = bytes,
+            "Dirty" => stats.dirty_bytes = bytes,
+            "Writeback" => stats.writeback_bytes = bytes,
+            "SwapTotal" => stats.swap_total_bytes = bytes,
+            "SwapFree" => stats.swap_free_bytes = bytes,
+            "SwapCached" => stats.swap_cached_bytes = bytes,
+            _ => {}
+        }
+    }
+
+    if stats.total_bytes == 0 { None } else { Some(stats) }
+}
+
+pub fn read_zram_devices() -> Vec<ZramDevice> {
+    let mut devices = Vec::new();
+
+    if let Ok(entries) = fs::read_dir("/sys/block") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("zram") {
+                continue;
+            }
+            if let Some(device) = read_zram_device(&entry.path(), name) {
+                devices.push(device);
+            }
+        }
+    }
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn read_zram_device(dir: &Path, name: String) -> Option<ZramDevice> {
+    // mm_stat: orig_data_size compr_data_size mem_used_total mem_limit mem_used_max same_pages ...
+    let mm_stat = fs::read_to_string(dir.join("mm_stat")).ok()?;
+    let fields: Vec<u64> = mm_stat.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+    let get = |i: usize| fields.get(i).copied().unwrap_or(0);
+
+    let disksize_bytes = fs::read_to_string(dir.join("disksize"))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
+
+    // comp_algorithm lists every option with the active one in brackets: "lzo [lz4] zstd"
+    let algorithm = fs::read_to_string(dir.join("comp_algorithm")).ok().and_then(|s| {
+        s.split_whitespace()
+            .find(|a| a.starts_with('['))
+            .map(|a| a.trim_matches(|c| c == '[' || c == ']').to_string())
+    });
+
+    let (orig, compr) = (get(0), get(1));
+    Some(ZramDevice {
+        name,
+        algorithm,
+        disksize_bytes,
+        orig_data_bytes: orig,
+        compr_data_bytes: compr,
+        mem_used_bytes: get(2),
+        mem_limit_bytes: get(3),
+        mem_used_max_bytes: get(4),
+        compression_ratio: if compr > 0 { Some(orig as f64 / compr as f64) } else { None },
+    })
+}
@@ -1,7 +1,8 @@
 use std::fs;
 use std::path::Path;
-use std::process::{Command, Stdio};
-use std::io::Write;
+use crate::hardware::enumerate_gpus;
+use crate::intel_gpu::{hardware_freq_range, is_intel, set_gpu_freq_limits, GpuFreqLimits};
+use crate::permissions::privileged_write;
 
 #[derive(Debug, Clone)]
 pub enum Mode {
@@ -12,6 +13,18 @@ pub enum Mode {
 }
 
 impl Mode {
+    /// Intel GPU clock window for this mode within the hardware range `rpn..=rp0`.
+    /// Custom modes only change the CPU governor.
+    pub fn intel_gpu_limits(&self, rpn: u32, rp0: u32) -> Option<GpuFreqLimits> {
+        let mid = rpn + rp0.saturating_sub(rpn) / 2;
+        match self {
+            Mode::BatterySaver => Some(GpuFreqLimits { min: Some(rpn), max: Some(mid), boost: Some(mid) }),
+            Mode::Balanced => Some(GpuFreqLimits { min: Some(rpn), max: Some(rp0), boost: Some(rp0) }),
+            Mode::Performance => Some(GpuFreqLimits { min: Some(mid), max: Some(rp0), boost: Some(rp0) }),
+            Mode::Custom(_) => None,
+        }
+    }
+
     pub fn from_str(name: &str) -> Option<Mode> {
         match name.to_lowercase().as_str() {
             "battery" | "saver" => Some(Mode::BatterySaver),
@@ -22,6 +35,18 @@ impl Mode {
     }
 }
 
+/// Best guess at the active profile from the CPU governor, since modes don't persist any state.
+/// schedutil is shared by Battery Saver and Balanced fallbacks and reads as Balanced.
+pub fn active_profile(governor: Option<&str>) -> String {
+    match governor {
+        Some("performance") => "Performance".to_string(),
+        Some("powersave") => "Battery Saver".to_string(),
+        Some("ondemand") | Some("schedutil") => "Balanced".to_string(),
+        Some(other) => format!("Custom ({})", other),
+        None => "Unknown".to_string(),
+    }
+}
+
 pub fn apply_mode(mode: &Mode) {
     let available_governors = get_available_governors();
     println!("[Mode] Available governors: {:?}", available_governors);
@@ -46,6 +71,24 @@ pub fn apply_mode(mode: &Mode) {
     } else {
         eprintln!("[Mode] No compatible governor found for the selected mode.");
     }
+
+    apply_intel_gpu_limits(mode);
+}
+
+/// Applies the mode's GPU clock window to every Intel card that exposes frequency controls.
+fn apply_intel_gpu_limits(mode: &Mode) {
+    for card in enumerate_gpus().into_iter().filter(is_intel) {
+        let (rpn, rp0) = match hardware_freq_range(&card) {
+            Some(range) => range,
+            None => continue,
+        };
+        if let Some(limits) = mode.intel_gpu_limits(rpn, rp0) {
+            match set_gpu_freq_limits(&card, &limits) {
+                Ok(()) => println!("[Mode] {} GPU clocks set to {}", card.name, limits),
+                Err(e) => eprintln!("[Mode] {}: {}", card.name, e),
+            }
+        }
+    }
 }
 
 fn find_first_match(available: &[String], preferred: &[&str]) -> Option<String> {
@@ -60,15 +103,16 @@ fn find_first_match(available: &[String], preferred: &[&str]) -> Option<String>
 pub fn reset_to_default() {
     println!("[Reset] Reverting to default...");
 
+    // Give Intel GPUs their full hardware clock range back
+    apply_intel_gpu_limits(&Mode::Balanced);
+
     let fallback_governors = ["ondemand", "schedutil", "powersave", "performance"];
     let available = get_available_governors();
 
     for gov in &fallback_governors {
-        if available.contains(&gov.to_string()) {
-            if try_set_cpu_governor(gov) {
-                println!("[Reset] Set default governor: {}", gov);
-                return;
-            }
+        if available.contains(&gov.to_string()) && try_set_cpu_governor(gov) {
+            println!("[Reset] Set default governor: {}", gov);
+            return;
         }
     }
 
@@ -100,18 +144,7 @@ fn try_set_cpu_governor(governor: &str) -> bool {
 
             let gov_path = path.join("cpufreq/scaling_governor");
             if gov_path.exists() {
-                let child = Command::new("sudo")
-                    .arg("tee")
-                    .arg(gov_path.to_string_lossy().to_string())
-                    .stdin(Stdio::piped())
-                    .spawn();
-
-                if let Ok(mut child) = child {
-                    if let Some(stdin) = child.stdin.as_mut() {
-                        let _ = stdin.write_all(governor.as_bytes());
-                    }
-                    let _ = child.wait();
-                }
+                privileged_write(&gov_path, governor);
             }
         }
         true
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::read_trimmed;
+
+/// Cumulative counters for one interface from /proc/net/dev.
+#[derive(Debug, Clone, Default)]
+pub struct NetCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// One read of /proc/net/dev and when it was taken.
+#[derive(Debug, Clone)]
+pub struct NetSample {
+    pub at: Instant,
+    pub interfaces: Vec<NetCounters>,
+}
+
+/// Wi-Fi link quality from /proc/net/wireless. SSID and bitrate need nl80211, so they're not here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WifiLink {
+    pub link_quality: Option<f64>, // Driver-defined scale, usually out of 70
+    pub signal_dbm: Option<f64>,
+    pub noise_dbm: Option<f64>,
+}
+
+/// Rates for one interface between two samples. Error and drop counts are cumulative.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkUsage {
+    pub name: String,
+    pub operstate: Option<String>,     // "up", "down", "dormant", ...
+    pub link_speed_mbps: Option<u32>,  // Wired links only; Wi-Fi reports no speed in sysfs
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub wifi: Option<WifiLink>,
+}
+
+/// Reads every interface except loopback.
+pub fn read_net_dev() -> Option<NetSample> {
+    let content = fs::read_to_string("/proc/net/dev").ok()?;
+    let mut interfaces = Vec::new();
+
+    // Two header lines, then "  eth0: rx_bytes rx_packets errs drop fifo frame compressed multicast tx_bytes ..."
+    for line in content.lines().skip(2) {
+        let (name, rest) = match line.split_once(':') {
+            Some((name, rest)) => (name.trim(), rest),
+            None => continue,
+        };
+        if name == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = rest.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        if fields.len() < 16 {
+            continue;
+        }
+        interfaces.push(NetCounters {
+            name: name.to_string(),
+            rx_bytes: fields[0],
+            rx_packets: fields[1],
+            rx_errors: fields[2],
+            rx_dropped: fields[3],
+            tx_bytes: fields[8],
+            tx_packets: fields[9],
+            tx_errors: fields[10],
+            tx_dropped: fields[11],
+        });
+    }
+
+    Some(NetSample { at: Instant::now(), interfaces })
+}
+
+/// Per-interface rates between two samples. Interfaces missing from `prev` are skipped.
+pub fn network_usage_between(prev: &NetSample, curr: &NetSample) -> Vec<NetworkUsage> {
+    let secs = curr.at.duration_since(prev.at).as_secs_f64();
+    if secs <= 0.0 {
+        return Vec::new();
+    }
+    let wireless = fs::read_to_string("/proc/net/wireless").unwrap_or_default();
+
+    curr.interfaces
+        .iter()
+        .filter_map(|c| {
+            let p = prev.interfaces.iter().find(|p| p.name == c.name)?;
+            let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+            let sys = Path::new("/sys/class/net").join(&c.name);
+
+            Some(NetworkUsage {
+                name: c.name.clone(),
+                operstate: read_trimmed(&sys.join("operstate")),
+                // Reads as -1 (or fails) while the link is down
+                link_speed_mbps: read_trimmed(&sys.join("speed"))
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .filter(|&v| v > 0)
+                    .map(|v| v as u32),
+                rx_bytes_per_sec: rate(c.rx_bytes, p.rx_bytes),
+                tx_bytes_per_sec: rate(c.tx_bytes, p.tx_bytes),
+                rx_packets_per_sec: rate(c.rx_packets, p.rx_packets),
+                tx_packets_per_sec: rate(c.tx_packets, p.tx_packets),
+                rx_errors: c.rx_errors,
+                tx_errors: c.tx_errors,
+                rx_dropped: c.rx_dropped,
+                tx_dropped: c.tx_dropped,
+                wifi: if sys.join("wireless").exists() { parse_wireless(&wireless, &c.name) } else { None },
+            })
+        })
+        .collect()
+}
+
+fn parse_wireless(content: &str, name: &str) -> Option<WifiLink> {
+    // " wlan0: 0000   54.  -56.  -256  ..." — status, then link/level/noise with trailing dots
+    let line = content.lines().skip(2).find(|l| l.trim_start().starts_with(&format!("{}:", name)))?;
+    let (_, rest) = line.split_once(':')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let value = |i: usize| fields.get(i).and_then(|v| v.trim_end_matches('.').parse::<f64>().ok());
+
+    Some(WifiLink {
+        link_quality: value(1),
+        signal_dbm: value(2),
+        // -256 means the driver doesn't report noise
+        noise_dbm: value(3).filter(|&n| n > -256.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIRELESS: &str = "\
+Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+wlp2s0: 0000   54.  -56.  -256        0      0      0      0     12        0
+ wlan1: 0000   70.  -38.  -92.        0      0      0      0      0        0
+";
+
+    #[test]
+    fn parses_wireless_after_header_lines() {
+        let link = parse_wireless(WIRELESS, "wlp2s0").unwrap();
+        assert_eq!(link.link_quality, Some(54.0));
+        assert_eq!(link.signal_dbm, Some(-56.0));
+        // -256 is the driver's "no noise reading" marker
+        assert_eq!(link.noise_dbm, None);
+
+        let link = parse_wireless(WIRELESS, "wlan1").unwrap();
+        assert_eq!(link.link_quality, Some(70.0));
+        assert_eq!(link.signal_dbm, Some(-38.0));
+        assert_eq!(link.noise_dbm, Some(-92.0));
+    }
+
+    #[test]
+    fn header_and_unknown_interfaces_are_not_matched() {
+        assert!(parse_wireless(WIRELESS, "face").is_none());
+        assert!(parse_wireless(WIRELESS, "wlp2s").is_none());
+        assert!(parse_wireless(WIRELESS, "eth0").is_none());
+        // No interfaces in wireless mode leaves only the headers
+        let headers: String = WIRELESS.lines().take(2).map(|l| format!("{}\n", l)).collect();
+        assert!(parse_wireless(&headers, "wlp2s0").is_none());
+    }
+}
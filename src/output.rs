@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Output formats for structured reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn from_str(name: &str) -> Option<OutputFormat> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "yaml" => Some(OutputFormat::Yaml),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes any record as JSON, YAML, or a one-row CSV with flattened column names, always
+/// ending in a newline. CSV columns follow the record's shape, so they vary with the hardware
+/// present (core count, disks, batteries): compare files by header name, not column position.
+/// `Text` has no generic rendering, so callers print their own human-readable report.
+pub fn render<T: Serialize>(record: &T, format: OutputFormat) -> Result<String, String> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(record).map(|s| s + "\n").map_err(|e| e.to_string()),
+        OutputFormat::Yaml => serde_yaml::to_string(record).map_err(|e| e.to_string()),
+        OutputFormat::Csv => {
+            let value = serde_json::to_value(record).map_err(|e| e.to_string())?;
+            write_csv(&[value])
+        }
+        OutputFormat::Text => Err("text output has no structured rendering".to_string()),
+    }
+}
+
+/// Flattens nested objects and arrays into dotted column names, e.g.
+/// `cpu_breakdown.user_percent` or `per_core.3`. Only scalar leaves are kept.
+pub fn flatten_json(value: &Value) -> Map<String, Value> {
+    let mut out = Map::new();
+    flatten_into(&mut out, String::new(), value);
+    out
+}
+
+fn flatten_into(out: &mut Map<String, Value>, prefix: String, value: &Value) {
+    let join = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten_into(out, join(key), child);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_into(out, join(&i.to_string()), child);
+            }
+        }
+        scalar => {
+            out.insert(prefix, scalar.clone());
+        }
+    }
+}
+
+/// Writes records as CSV. Columns are the union of every record's flattened fields in
+/// first-seen order, so hardware that appears mid-way (a second battery, a new disk) still fits.
+pub fn write_csv(records: &[Value]) -> Result<String, String> {
+    let rows: Vec<Map<String, Value>> = records.iter().map(flatten_json).collect();
+    let mut columns: Vec<&String> = Vec::new();
+    let mut seen = HashSet::new();
+    for row in &rows {
+        for key in row.keys() {
+            if seen.insert(key) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&columns).map_err(|e| e.to_string())?;
+    for row in &rows {
+        let cells = columns.iter().map(|c| row.get(c.as_str()).map(csv_cell).unwrap_or_default());
+        writer.write_record(cells).map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Unix seconds as "YYYY-MM-DD HH:MM:SS" UTC.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant), so no date crate is needed for one format
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn structured_formats_end_with_a_newline() {
+        let record = json!({ "schema_version": 2, "per_core": [12.5, 40.0] });
+        for format in [OutputFormat::Json, OutputFormat::Yaml, OutputFormat::Csv] {
+            let text = render(&record, format).unwrap();
+            assert!(text.ends_with('\n') && !text.ends_with("\n\n"), "{:?}: {:?}", format, text);
+        }
+    }
+
+    #[test]
+    fn csv_columns_are_the_union_in_first_seen_order() {
+        let records = [
+            json!({ "timestamp": 1, "cpu": { "user": 5 } }),
+            json!({ "timestamp": 2, "cpu": { "user": 7 }, "batteries": [{ "percent": 80 }] }),
+        ];
+        let csv = write_csv(&records).unwrap();
+        assert_eq!(csv, "timestamp,cpu.user,batteries.0.percent\n1,5,\n2,7,80\n");
+    }
+}
@@ -0,0 +1,126 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const APP_DIR: &str = "steam-deck-optimizer";
+
+/// Directory the tool wrote logs to before they moved under the state directory.
+const LEGACY_LOGS_DIR: &str = "logs";
+
+/// `$XDG_STATE_HOME/steam-deck-optimizer`, or `~/.local/state/steam-deck-optimizer`.
+pub fn state_dir() -> PathBuf {
+    xdg_dir("XDG_STATE_HOME", ".local/state").join(APP_DIR)
+}
+
+/// `$XDG_CONFIG_HOME/steam-deck-optimizer`, or `~/.config/steam-deck-optimizer`.
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config").join(APP_DIR)
+}
+
+/// Where snapshots, sessions and the telemetry store live.
+pub fn logs_dir() -> PathBuf {
+    state_dir().join("logs")
+}
+
+/// Files moved from `./logs` by this run; the migration runs at most once per process.
+static MIGRATED: OnceLock<usize> = OnceLock::new();
+
+/// The kinds of file this tool writes to the logs directory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogKind {
+    Snapshot, // system_log_<secs>.json
+    Session,  // session_<secs>.jsonl, optionally .gz or .zst after retention
+    Store,    // telemetry.db and its -wal/-shm files
+}
+
+/// Classifies a file name by this tool's exact naming, so other programs' files that share a
+/// prefix (`session_1.log`, `system_log_old.txt`) are never moved, compressed or deleted.
+pub fn log_kind(name: &str) -> Option<LogKind> {
+    let stamped = |rest: &str, suffix: &str| {
+        rest.strip_suffix(suffix)
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    };
+    if let Some(rest) = name.strip_prefix("system_log_") {
+        return stamped(rest, ".json").then_some(LogKind::Snapshot);
+    }
+    if let Some(rest) = name.strip_prefix("session_") {
+        let session = [".jsonl", ".jsonl.gz", ".jsonl.zst"].iter().any(|suffix| stamped(rest, suffix));
+        return session.then_some(LogKind::Session);
+    }
+    matches!(name, "telemetry.db" | "telemetry.db-wal" | "telemetry.db-shm").then_some(LogKind::Store)
+}
+
+/// Creates the logs directory.
+pub fn ensure_logs_dir() -> io::Result<PathBuf> {
+    let dir = logs_dir();
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// `ensure_logs_dir` for commands that write logs: also moves over anything older versions
+/// left in `./logs`. Read-only commands never migrate.
+pub fn prepare_logs_dir() -> io::Result<PathBuf> {
+    let dir = ensure_logs_dir()?;
+    MIGRATED.get_or_init(|| migrate_legacy_logs(&dir));
+    Ok(dir)
+}
+
+/// Whether this run moved legacy files, in which case automatic retention waits for the next
+/// run rather than pruning files the user hasn't seen in their new location yet.
+pub fn migrated_this_run() -> bool {
+    MIGRATED.get().is_some_and(|&moved| moved > 0)
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> PathBuf {
+    // The spec says relative values are invalid and must be ignored
+    match env::var_os(var).map(PathBuf::from) {
+        Some(path) if path.is_absolute() => path,
+        _ => PathBuf::from(env::var("HOME").unwrap_or_default()).join(home_fallback),
+    }
+}
+
+/// Whether the working directory is inside a git work tree, where `./logs` is more likely a
+/// project's own directory (this repository keeps sample logs there) than an old install's output.
+fn inside_git_work_tree() -> bool {
+    env::current_dir().is_ok_and(|cwd| cwd.ancestors().any(|dir| dir.join(".git").exists()))
+}
+
+fn migrate_legacy_logs(dir: &Path) -> usize {
+    if inside_git_work_tree() {
+        return 0;
+    }
+    let legacy = Path::new(LEGACY_LOGS_DIR);
+    let same_dir = match (legacy.canonicalize(), dir.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => true, // No ./logs at all
+    };
+    if same_dir {
+        return 0;
+    }
+    let entries = match fs::read_dir(legacy) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut moved = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let target = dir.join(&name);
+        if log_kind(&name).is_none() || target.exists() {
+            continue;
+        }
+        // rename fails across filesystems, so fall back to copy + delete
+        let result = fs::rename(entry.path(), &target)
+            .or_else(|_| fs::copy(entry.path(), &target).and_then(|_| fs::remove_file(entry.path())));
+        match result {
+            Ok(()) => moved += 1,
+            Err(e) => eprintln!("[Logs] Could not move {}: {}", entry.path().display(), e),
+        }
+    }
+    if moved > 0 {
+        println!("[Logs] Moved {} files from ./logs to {}", moved, dir.display());
+    }
+    moved
+}
@@ -1,9 +1,9 @@
-use std::fs::{self, OpenOptions};
+use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
-#[cfg(unix)]
-use std::os::unix::fs::MetadataExt;
 use std::env;
+use crate::hardware::enumerate_gpus;
 
 #[cfg(unix)]
 pub fn ensure_gpu_permissions() {
@@ -16,7 +16,7 @@ pub fn ensure_gpu_permissions() {
 
             let username = env::var("USER").unwrap_or_else(|_| "unknown".into());
             let _ = Command::new("sudo")
-                .args(&["usermod", "-aG", "video", &username])
+                .args(["usermod", "-aG", "video", &username])
                 .status()
                 .expect("Failed to run usermod");
 
@@ -58,10 +58,10 @@ SUBSYSTEM=="drm", GROUP="video", MODE="0660"
 
         println!("[+] Reloading udev rules...");
         let _ = Command::new("sudo")
-            .args(&["udevadm", "control", "--reload-rules"])
+            .args(["udevadm", "control", "--reload-rules"])
             .status();
         let _ = Command::new("sudo")
-            .args(&["udevadm", "trigger"])
+            .args(["udevadm", "trigger"])
             .status();
     }
 
@@ -75,22 +75,53 @@ SUBSYSTEM=="drm", GROUP="video", MODE="0660"
     if !debugfs_check {
         println!("[+] Mounting debugfs...");
         let _ = Command::new("sudo")
-            .args(&["mount", "-t", "debugfs", "none", "/sys/kernel/debug"])
+            .args(["mount", "-t", "debugfs", "none", "/sys/kernel/debug"])
             .status();
     }
 
-    // 4. Runtime Group and permission fix on amdgpu_pm_info
-    let pm_info = "/sys/kernel/debug/dri/0000:c6:00.0/amdgpu_pm_info";
-    #[cfg(unix)]
-    if fs::metadata(pm_info).is_ok() {
-        println!("[+] Fixing amdgpu_pm_info permissions for runtime access...");
+    // 4. Runtime Group and permission fix on amdgpu_pm_info for every AMD card
+    for card in enumerate_gpus().into_iter().filter(|c| c.is_amd()) {
+        let slot = match &card.pci_slot {
+            Some(slot) => slot,
+            None => continue,
+        };
+        let pm_info = format!("/sys/kernel/debug/dri/{}/amdgpu_pm_info", slot);
+        if fs::metadata(&pm_info).is_ok() {
+            println!("[+] Fixing amdgpu_pm_info permissions for runtime access ({})...", card.name);
 
-        let _ = Command::new("sudo")
-            .args(&["chgrp", "video", pm_info])
-            .status();
-        
-        let _ = Command::new("sudo")
-            .args(&["chmod", "660", pm_info])
-            .status();
+            let _ = Command::new("sudo")
+                .args(["chgrp", "video", &pm_info])
+                .status();
+
+            let _ = Command::new("sudo")
+                .args(["chmod", "660", &pm_info])
+                .status();
+        }
+    }
+}
+
+/// Writes `value` to a root-owned sysfs file through `sudo tee`, the same path used for governors.
+/// Returns true if tee exited successfully.
+pub fn privileged_write(path: &Path, value: &str) -> bool {
+    let child = Command::new("sudo")
+        .arg("tee")
+        .arg(path.to_string_lossy().to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            if let Some(stdin) = child.stdin.as_mut() {
+                let _ = stdin.write_all(value.as_bytes());
+            }
+            // Close stdin so tee sees EOF before we wait on it
+            drop(child.stdin.take());
+            child.wait().map(|s| s.success()).unwrap_or(false)
+        }
+        Err(e) => {
+            eprintln!("[Permissions] Failed to run sudo tee for {}: {}", path.display(), e);
+            false
+        }
     }
 }
@@ -0,0 +1,268 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::permissions::privileged_write;
+use crate::hardware::read_trimmed;
+
+/// Time estimates use the average power draw over this window, so a short spike
+/// doesn't swing the "time left" figure.
+pub const AVERAGE_WINDOW: Duration = Duration::from_secs(60);
+
+/// One battery from /sys/class/power_supply, converted to W, Wh, V and A.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    pub name: String,                 // e.g. "BAT1"
+    pub status: Option<String>,       // "Charging", "Discharging", "Full", "Not charging", ...
+    pub capacity_percent: Option<u8>,
+    pub energy_now_wh: Option<f64>,
+    pub energy_full_wh: Option<f64>,
+    pub energy_full_design_wh: Option<f64>,
+    pub health_percent: Option<f64>,  // energy_full / energy_full_design
+    pub voltage_v: Option<f64>,
+    pub current_a: Option<f64>,       // Always positive; `status` gives the direction
+    pub power_w: Option<f64>,         // power_now, or current * voltage
+    pub cycle_count: Option<u32>,
+    pub charge_start_threshold: Option<u8>, // charge_control_start_threshold: resume charging below this %
+    pub charge_end_threshold: Option<u8>,   // charge_control_end_threshold: stop charging at this %
+
+    // Filled in by `PowerAverager`
+    pub avg_power_w: Option<f64>,
+    pub time_to_empty_min: Option<f64>,
+    pub time_to_full_min: Option<f64>,
+}
+
+impl BatteryInfo {
+    pub fn is_charging(&self) -> bool {
+        self.status.as_deref() == Some("Charging")
+    }
+
+    pub fn is_discharging(&self) -> bool {
+        self.status.as_deref() == Some("Discharging")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerSupplies {
+    pub ac_online: Option<bool>, // None when there is no Mains/USB supply to ask
+    pub batteries: Vec<BatteryInfo>,
+}
+
+/// Reads every Battery, Mains and USB supply under /sys/class/power_supply.
+pub fn read_power_supplies() -> PowerSupplies {
+    let mut supplies = PowerSupplies::default();
+    let entries = match fs::read_dir("/sys/class/power_supply") {
+        Ok(entries) => entries,
+        Err(_) => return supplies,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let dir = entry.path();
+        match read_trimmed(&dir.join("type")).as_deref() {
+            Some("Battery") => {
+                // Peripheral batteries (controllers, mice) report scope=Device
+                if read_trimmed(&dir.join("scope")).as_deref() == Some("Device") {
+                    continue;
+                }
+                supplies.batteries.push(read_battery(&dir, name));
+            }
+            Some("Mains") | Some("USB") => {
+                if let Some(online) = read_i64(&dir.join("online")) {
+                    supplies.ac_online = Some(supplies.ac_online.unwrap_or(false) || online == 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    supplies.batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    supplies
+}
+
+fn read_battery(dir: &Path, name: String) -> BatteryInfo {
+    // sysfs uses micro-units: µV, µA, µW, µWh, µAh
+    let micro = |file: &str| read_i64(&dir.join(file)).map(|v| v as f64 / 1_000_000.0);
+
+    let voltage_v = micro("voltage_now");
+    let current_a = micro("current_now").map(f64::abs);
+    let power_w = micro("power_now").map(f64::abs).or(match (current_a, voltage_v) {
+        (Some(a), Some(v)) => Some(a * v),
+        _ => None,
+    });
+
+    // Batteries that only report charge (Ah) are converted with the design voltage
+    let design_voltage = micro("voltage_min_design").or(voltage_v);
+    let energy = |energy_file: &str, charge_file: &str| {
+        micro(energy_file).or_else(|| match (micro(charge_file), design_voltage) {
+            (Some(ah), Some(v)) => Some(ah * v),
+            _ => None,
+        })
+    };
+    let energy_now_wh = energy("energy_now", "charge_now");
+    let energy_full_wh = energy("energy_full", "charge_full");
+    let energy_full_design_wh = energy("energy_full_design", "charge_full_design");
+
+    let health_percent = match (energy_full_wh, energy_full_design_wh) {
+        (Some(full), Some(design)) if design > 0.0 => Some(100.0 * full / design),
+        _ => None,
+    };
+
+    BatteryInfo {
+        status: read_trimmed(&dir.join("status")),
+        capacity_percent: read_percent(&dir.join("capacity")),
+        energy_now_wh,
+        energy_full_wh,
+        energy_full_design_wh,
+        health_percent,
+        voltage_v,
+        current_a,
+        power_w,
+        cycle_count: read_i64(&dir.join("cycle_count")).filter(|&c| c >= 0).map(|c| c as u32),
+        charge_start_threshold: read_percent(&dir.join("charge_control_start_threshold")),
+        charge_end_threshold: read_percent(&dir.join("charge_control_end_threshold")),
+        name,
+        ..BatteryInfo::default()
+    }
+}
+
+/// Requested charge thresholds in percent; `None` leaves that threshold unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChargeThresholds {
+    pub start: Option<u8>,
+    pub end: Option<u8>,
+}
+
+impl fmt::Display for ChargeThresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: Option<u8>| v.map_or("unchanged".to_string(), |v| format!("{}%", v));
+        write!(f, "start {}, end {}", show(self.start), show(self.end))
+    }
+}
+
+/// The first system battery that supports a charge end threshold, as (name, sysfs dir).
+pub fn charge_control_battery() -> Option<(String, PathBuf)> {
+    let mut candidates: Vec<(String, PathBuf)> = fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+        .filter(|(_, dir)| {
+            read_trimmed(&dir.join("type")).as_deref() == Some("Battery")
+                && dir.join("charge_control_end_threshold").exists()
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// Writes the charge thresholds through the privileged write path and returns the battery name.
+pub fn set_charge_thresholds(thresholds: &ChargeThresholds) -> Result<String, String> {
+    let (name, dir) = charge_control_battery().ok_or("no battery exposes charge_control_end_threshold")?;
+    let start_path = dir.join("charge_control_start_threshold");
+    let end_path = dir.join("charge_control_end_threshold");
+
+    if thresholds.start.is_some() && !start_path.exists() {
+        return Err(format!("{} has no charge start threshold", name));
+    }
+    let current_start = read_percent(&start_path);
+    let current_end = read_percent(&end_path).unwrap_or(100);
+    let start = thresholds.start.or(current_start);
+    let end = thresholds.end.unwrap_or(current_end);
+
+    if !(1..=100).contains(&end) {
+        return Err(format!("end threshold {}% is outside 1-100%", end));
+    }
+    if let Some(start) = start {
+        if start >= end {
+            return Err(format!("start threshold {}% must be below the end threshold {}%", start, end));
+        }
+    }
+
+    // The driver rejects a start at or above the current end (and vice versa), so raise
+    // the end first when the window moves up and lower the start first when it moves down.
+    let mut writes: Vec<(&Path, u8)> = Vec::new();
+    let end_write = thresholds.end.map(|v| (end_path.as_path(), v));
+    let start_write = thresholds.start.map(|v| (start_path.as_path(), v));
+    if end >= current_end {
+        writes.extend(end_write);
+        writes.extend(start_write);
+    } else {
+        writes.extend(start_write);
+        writes.extend(end_write);
+    }
+
+    for (path, value) in writes {
+        if !privileged_write(path, &value.to_string()) {
+            return Err(format!("failed to write {}% to {}", value, path.display()));
+        }
+    }
+    Ok(name)
+}
+
+/// Rolling average of each battery's power draw, used for the time-to-empty/full estimates.
+/// The window restarts whenever a battery switches between charging and discharging.
+#[derive(Default)]
+pub struct PowerAverager {
+    windows: HashMap<String, PowerWindow>,
+}
+
+#[derive(Default)]
+struct PowerWindow {
+    status: Option<String>,
+    samples: VecDeque<(Instant, f64)>, // (when, watts)
+}
+
+impl PowerAverager {
+    pub fn update(&mut self, supplies: &mut PowerSupplies) {
+        let now = Instant::now();
+
+        for battery in &mut supplies.batteries {
+            let entry = self.windows.entry(battery.name.clone()).or_default();
+            if entry.status != battery.status {
+                entry.samples.clear();
+                entry.status = battery.status.clone();
+            }
+            let window = &mut entry.samples;
+            if let Some(watts) = battery.power_w {
+                window.push_back((now, watts));
+            }
+            while window.front().is_some_and(|(t, _)| now.duration_since(*t) > AVERAGE_WINDOW) {
+                window.pop_front();
+            }
+            if window.is_empty() {
+                continue;
+            }
+
+            let avg = window.iter().map(|(_, w)| w).sum::<f64>() / window.len() as f64;
+            battery.avg_power_w = Some(avg);
+            if avg <= 0.0 {
+                continue;
+            }
+            if battery.is_discharging() {
+                battery.time_to_empty_min = battery.energy_now_wh.map(|wh| 60.0 * wh / avg);
+            } else if battery.is_charging() {
+                battery.time_to_full_min = match (battery.energy_full_wh, battery.energy_now_wh) {
+                    (Some(full), Some(now_wh)) => Some(60.0 * (full - now_wh).max(0.0) / avg),
+                    _ => None,
+                };
+            }
+        }
+    }
+}
+
+/// "3h 25m" style formatting for the time estimates.
+pub fn format_minutes(minutes: f64) -> String {
+    let total = minutes.round() as u64;
+    format!("{}h {:02}m", total / 60, total % 60)
+}
+
+fn read_percent(path: &Path) -> Option<u8> {
+    read_i64(path).map(|v| v.clamp(0, 100) as u8)
+}
+
+fn read_i64(path: &Path) -> Option<i64> {
+    read_trimmed(path)?.parse::<i64>().ok()
+}
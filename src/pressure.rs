@@ -0,0 +1,90 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+/// One "some" or "full" line of a /proc/pressure file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureLine {
+    pub avg10: f64,    // % of the last 10 s that tasks were stalled
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64, // Cumulative stall time in microseconds
+}
+
+/// "some": at least one task stalled. "full": all non-idle tasks stalled (absent for cpu on older kernels).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pressure {
+    pub some: Option<PressureLine>,
+    pub full: Option<PressureLine>,
+}
+
+/// Pressure Stall Information for each resource. `None` when the kernel was built without PSI.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureStats {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+/// /proc/loadavg
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+    pub running_tasks: u32,
+    pub total_tasks: u32,
+}
+
+pub fn read_pressure() -> PressureStats {
+    PressureStats {
+        cpu: read_pressure_file("cpu"),
+        memory: read_pressure_file("memory"),
+        io: read_pressure_file("io"),
+    }
+}
+
+fn read_pressure_file(resource: &str) -> Option<Pressure> {
+    let content = fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+    let mut pressure = Pressure::default();
+
+    // e.g. "some avg10=0.00 avg60=0.12 avg300=0.05 total=123456"
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let mut parsed = PressureLine::default();
+        for field in parts {
+            match field.split_once('=') {
+                Some(("avg10", v)) => parsed.avg10 = v.parse().unwrap_or(0.0),
+                Some(("avg60", v)) => parsed.avg60 = v.parse().unwrap_or(0.0),
+                Some(("avg300", v)) => parsed.avg300 = v.parse().unwrap_or(0.0),
+                Some(("total", v)) => parsed.total_us = v.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => pressure.some = Some(parsed),
+            Some("full") => pressure.full = Some(parsed),
+            _ => {}
+        }
+    }
+
+    Some(pressure)
+}
+
+pub fn read_loadavg() -> Option<LoadAverage> {
+    // e.g. "0.52 0.58 0.59 2/1234 56789"
+    let content = fs::read_to_string("/proc/loadavg").ok()?;
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    if parts.len() < 4 {
+        return None;
+    }
+    let (running, total) = parts[3].split_once('/')?;
+
+    Some(LoadAverage {
+        load1: parts[0].parse().ok()?,
+        load5: parts[1].parse().ok()?,
+        load15: parts[2].parse().ok()?,
+        running_tasks: running.parse().unwrap_or(0),
+        total_tasks: total.parse().unwrap_or(0),
+    })
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::gpu_procs::GpuProcess;
+
+/// /proc reports CPU time in USER_HZ ticks; almost always 100, but it's configurable per architecture.
+fn ticks_per_sec() -> f64 {
+    static TICKS: OnceLock<f64> = OnceLock::new();
+    *TICKS.get_or_init(|| {
+        // SAFETY: sysconf only reads a system constant
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 { ticks as f64 } else { 100.0 }
+    })
+}
+
+/// /proc/<pid>/stat of every process at one point in time.
+#[derive(Debug, Clone)]
+pub struct ProcSample {
+    taken_at: Instant,
+    stats: HashMap<u32, ProcStat>,
+}
+
+/// One row of the process view.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub state: char,
+    pub cpu_percent: f64,       // Of one core, like top: a busy 4-thread process can show 400%
+    pub rss_bytes: u64,
+    pub threads: u32,
+    pub nice: i32,
+    pub cgroup: Option<String>, // cgroup v2 path, e.g. "/user.slice/user-1000.slice/app-steam.scope"
+    pub gpu_percent: f64,       // Busiest DRM engine, from fdinfo
+    pub vram_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+    Gpu,
+}
+
+impl ProcessSort {
+    pub fn from_str(s: &str) -> Option<ProcessSort> {
+        match s {
+            "cpu" => Some(ProcessSort::Cpu),
+            "mem" | "memory" => Some(ProcessSort::Memory),
+            "gpu" => Some(ProcessSort::Gpu),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "Memory",
+            ProcessSort::Gpu => "GPU",
+        }
+    }
+}
+
+/// Reads /proc/<pid>/stat for every process.
+pub fn sample_processes() -> ProcSample {
+    let mut stats = HashMap::new();
+
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+                Ok(pid) => pid,
+                Err(_) => continue,
+            };
+            if let Some(stat) = read_stat(pid) {
+                stats.insert(pid, stat);
+            }
+        }
+    }
+
+    ProcSample { taken_at: Instant::now(), stats }
+}
+
+/// Builds the process list from two samples, merging in GPU usage from fdinfo.
+/// Processes that started after `prev` show 0% CPU until the next sample.
+pub fn compute_process_usage(prev: &ProcSample, curr: &ProcSample, gpu: &[GpuProcess]) -> Vec<ProcessInfo> {
+    let secs = curr.taken_at.duration_since(prev.taken_at).as_secs_f64();
+    let mut processes = Vec::with_capacity(curr.stats.len());
+
+    for (&pid, stat) in &curr.stats {
+        let cpu_percent = match prev.stats.get(&pid) {
+            Some(before) if secs > 0.0 => {
+                100.0 * stat.cpu_ticks().saturating_sub(before.cpu_ticks()) as f64 / ticks_per_sec() / secs
+            }
+            _ => 0.0,
+        };
+        let gpu_proc = gpu.iter().find(|g| g.pid == pid);
+
+        processes.push(ProcessInfo {
+            pid,
+            name: stat.comm.clone(),
+            state: stat.state,
+            cpu_percent,
+            rss_bytes: read_status_kb(pid, "VmRSS").unwrap_or(0) * 1024,
+            threads: stat.num_threads,
+            nice: stat.nice,
+            cgroup: read_cgroup(pid),
+            gpu_percent: gpu_proc.map_or(0.0, |g| g.busy_percent()),
+            vram_bytes: gpu_proc.map_or(0, |g| g.vram_bytes),
+        });
+    }
+
+    sort_processes(&mut processes, ProcessSort::Cpu);
+    processes
+}
+
+pub fn sort_processes(processes: &mut [ProcessInfo], sort: ProcessSort) {
+    match sort {
+        ProcessSort::Cpu => processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+        ProcessSort::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.rss_bytes)),
+        ProcessSort::Gpu => processes.sort_by(|a, b| {
+            b.gpu_percent.total_cmp(&a.gpu_percent).then(b.vram_bytes.cmp(&a.vram_bytes))
+        }),
+    }
+}
+
+/// Proportional set size from /proc/<pid>/smaps_rollup. Walking a process's page tables is
+/// costly, so callers read this only for the rows they display.
+pub fn read_pss_bytes(pid: u32) -> Option<u64> {
+    let content = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Pss:"))
+        .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+#[derive(Debug, Clone)]
+struct ProcStat {
+    comm: String,
+    state: char,
+    utime: u64,
+    stime: u64,
+    nice: i32,
+    num_threads: u32,
+}
+
+impl ProcStat {
+    fn cpu_ticks(&self) -> u64 {
+        self.utime + self.stime
+    }
+}
+
+fn read_stat(pid: u32) -> Option<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // "pid (comm) state ppid ..." — comm can contain spaces and parentheses, so split at the last ')'
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let comm = content.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+    // fields[0] is field 3 (state) of proc(5)
+    let field = |n: usize| fields.get(n - 3).copied().unwrap_or_default();
+
+    Some(ProcStat {
+        comm,
+        state: field(3).chars().next().unwrap_or('?'),
+        utime: field(14).parse().unwrap_or(0),
+        stime: field(15).parse().unwrap_or(0),
+        nice: field(19).parse().unwrap_or(0),
+        num_threads: field(20).parse().unwrap_or(0),
+    })
+}
+
+fn read_status_kb(pid: u32, key: &str) -> Option<u64> {
+    let content = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .and_then(|v| v.split_whitespace().next()?.parse().ok())
+}
+
+fn read_cgroup(pid: u32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    // cgroup v2 is the "0::<path>" line; on v1-only systems take the first controller's path
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .or_else(|| content.lines().next().and_then(|line| line.splitn(3, ':').nth(2)))
+        .map(|path| path.to_string())
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::hardware::read_trimmed;
+
+/// Cumulative package energy from the powercap RAPL zones, and when it was read.
+#[derive(Debug, Clone)]
+pub struct RaplSample {
+    at: Instant,
+    zones: Vec<RaplZone>,
+}
+
+#[derive(Debug, Clone)]
+struct RaplZone {
+    name: String,      // e.g. "intel-rapl:0"
+    energy_uj: u64,
+    max_energy_uj: u64, // The counter wraps back to 0 past this
+}
+
+/// Reads every `package-N` zone. Both Intel and AMD Zen CPUs register under `intel-rapl`.
+/// `energy_uj` is root-only on most kernels, so this is often None for a normal user.
+pub fn read_package_energy() -> Option<RaplSample> {
+    let root = Path::new("/sys/class/powercap");
+    let mut zones = Vec::new();
+
+    for entry in fs::read_dir(root).ok()?.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Top-level zones only; "intel-rapl:0:0" and deeper are core/uncore subzones
+        if !name.starts_with("intel-rapl:") || name.matches(':').count() != 1 {
+            continue;
+        }
+        let zone_name = read_trimmed(&path.join("name")).unwrap_or_default();
+        if !zone_name.starts_with("package") {
+            continue;
+        }
+        let energy_uj = match read_trimmed(&path.join("energy_uj")).and_then(|v| v.parse().ok()) {
+            Some(v) => v,
+            None => continue,
+        };
+        let max_energy_uj = read_trimmed(&path.join("max_energy_range_uj"))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(u64::MAX);
+        zones.push(RaplZone { name, energy_uj, max_energy_uj });
+    }
+
+    if zones.is_empty() {
+        None
+    } else {
+        Some(RaplSample { at: Instant::now(), zones })
+    }
+}
+
+/// Average package power between two samples, summed over all packages.
+pub fn package_power_between(prev: &RaplSample, curr: &RaplSample) -> Option<f64> {
+    let secs = curr.at.duration_since(prev.at).as_secs_f64();
+    if secs <= 0.0 {
+        return None;
+    }
+    let mut microjoules = 0u64;
+    for zone in &curr.zones {
+        let before = prev.zones.iter().find(|z| z.name == zone.name)?;
+        microjoules += if zone.energy_uj >= before.energy_uj {
+            zone.energy_uj - before.energy_uj
+        } else {
+            // Wrapped around once since the last read
+            zone.max_energy_uj - before.energy_uj + zone.energy_uj
+        };
+    }
+    Some(microjoules as f64 / 1_000_000.0 / secs)
+}
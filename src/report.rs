@@ -0,0 +1,330 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::modes::active_profile;
+use crate::output::format_timestamp;
+use crate::sampler::Snapshot;
+use crate::schema::SessionLog;
+#[cfg(feature = "sqlite")]
+use crate::store::SessionRow;
+use crate::summary::{format_value, summarize};
+
+/// Charts keep at most this many points; longer sessions are averaged into buckets
+/// so a multi-hour recording still makes a small file.
+const MAX_POINTS: usize = 1500;
+
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 220.0;
+const MARGIN_LEFT: f64 = 48.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+
+/// Everything the report says about where the samples came from.
+#[derive(Debug, Clone, Default)]
+pub struct ReportMeta {
+    pub title: String, // "Session 3" or the file name
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub interval_ms: u64,
+    pub profile: String,
+    pub game: Option<String>,
+    pub device_model: Option<String>,
+    pub hostname: Option<String>,
+}
+
+impl ReportMeta {
+    #[cfg(feature = "sqlite")]
+    pub fn from_session_row(session: &SessionRow) -> ReportMeta {
+        ReportMeta {
+            title: format!("Session {}", session.id),
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            interval_ms: session.interval_ms,
+            profile: session.profile.clone(),
+            game: session.game.clone(),
+            device_model: session.device_model.clone(),
+            hostname: session.hostname.clone(),
+        }
+    }
+
+    /// From a session file's header and footer, falling back to the samples if it was cut short.
+    pub fn from_session_log(title: String, log: &SessionLog) -> ReportMeta {
+        let mut meta = ReportMeta::from_samples(title, &log.samples);
+        if let Some(info) = &log.info {
+            meta.started_at = info.started_at;
+            meta.interval_ms = info.interval_ms;
+            meta.profile = info.profile.clone();
+            meta.game = info.game.as_ref().map(|g| g.name.clone());
+            meta.device_model = info.device_model.clone();
+            meta.hostname = info.hostname.clone();
+        }
+        if let Some(end) = &log.end {
+            meta.ended_at = Some(end.ended_at);
+            meta.game = meta.game.or_else(|| end.game.as_ref().map(|g| g.name.clone()));
+        }
+        meta
+    }
+
+    /// For a lone `--log` snapshot, which has no session metadata.
+    pub fn from_samples(title: String, samples: &[Snapshot]) -> ReportMeta {
+        ReportMeta {
+            title,
+            started_at: samples.first().map_or(0, |s| s.timestamp),
+            ended_at: samples.last().map(|s| s.timestamp),
+            interval_ms: 1000,
+            profile: active_profile(samples.first().and_then(|s| s.cpu_policies.first()).and_then(|p| p.governor.as_deref())),
+            ..ReportMeta::default()
+        }
+    }
+}
+
+/// One line on a chart: a value per sample, None where it wasn't available.
+struct Series {
+    label: &'static str,
+    color: &'static str,
+    values: Vec<Option<f64>>,
+}
+
+/// (seconds since the start, value) after downsampling.
+type Points = Vec<(f64, Option<f64>)>;
+
+struct Chart {
+    title: &'static str,
+    unit: &'static str,
+    range: Option<(f64, f64)>, // Fixed y range for percentages; otherwise fitted to the data
+    series: Vec<Series>,
+}
+
+/// Renders a standalone HTML page: metadata, the session summary and SVG charts over time.
+/// No scripts, stylesheets or fonts are loaded from anywhere, so it opens offline.
+pub fn render_html(meta: &ReportMeta, samples: &[Snapshot]) -> String {
+    let summary = summarize(samples, Duration::from_millis(meta.interval_ms));
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape(&format!("{} — Steam Deck Optimizer report", meta.title)),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&meta.title));
+
+    let _ = writeln!(html, "<h2>Session</h2>\n<table class=\"meta\">");
+    let rows = [
+        ("Started (UTC)", format_timestamp(meta.started_at)),
+        ("Ended (UTC)", meta.ended_at.map_or("not recorded".to_string(), format_timestamp)),
+        ("Duration", format!("{} min", format_value(Some(summary.duration_minutes())))),
+        ("Samples", format!("{} every {} ms", summary.samples, meta.interval_ms)),
+        ("Profile", meta.profile.clone()),
+        ("Game", meta.game.clone().unwrap_or_else(|| "-".to_string())),
+        ("Device", meta.device_model.clone().unwrap_or_else(|| "-".to_string())),
+        ("Host", meta.hostname.clone().unwrap_or_else(|| "-".to_string())),
+    ];
+    for (label, value) in rows {
+        let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", label, escape(&value));
+    }
+    let _ = writeln!(html, "</table>");
+
+    let _ = writeln!(html, "<h2>Summary</h2>\n<table class=\"summary\">");
+    for row in summary.rows() {
+        let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", escape(row.label), format_value(row.value));
+    }
+    let _ = writeln!(html, "</table>");
+
+    let _ = writeln!(html, "<h2>Over time</h2>");
+    let offsets: Vec<f64> = samples.iter().map(|s| s.timestamp.saturating_sub(meta.started_at) as f64).collect();
+    for chart in charts(samples) {
+        html.push_str(&render_chart(&chart, &offsets));
+    }
+
+    let _ = writeln!(html, "<p class=\"footer\">Generated {}</p>\n</body>\n</html>", format_timestamp(now_secs()));
+    html
+}
+
+fn charts(samples: &[Snapshot]) -> Vec<Chart> {
+    let series = |label, color, value: fn(&Snapshot) -> Option<f64>| Series {
+        label,
+        color,
+        values: samples.iter().map(value).collect(),
+    };
+    vec![
+        Chart {
+            title: "CPU load",
+            unit: "%",
+            range: Some((0.0, 100.0)),
+            series: vec![series("CPU", "#1f77b4", |s| s.accurate_cpu_total.or(s.sysinfo_cpu_total.map(f64::from)))],
+        },
+        Chart {
+            title: "GPU load",
+            unit: "%",
+            range: Some((0.0, 100.0)),
+            series: vec![series("GPU", "#d62728", |s| s.gpu_util_percent.map(f64::from))],
+        },
+        Chart {
+            title: "Temperature",
+            unit: "°C",
+            range: None,
+            series: vec![
+                series("GPU", "#d62728", |s| s.gpu_temp_celsius.map(f64::from)),
+                series("Hottest zone", "#ff7f0e", |s| s.thermal_zones.iter().filter_map(|z| z.temp_c).reduce(f64::max)),
+            ],
+        },
+        Chart {
+            title: "Power",
+            unit: "W",
+            range: None,
+            series: vec![
+                series("Package", "#9467bd", |s| s.package_power_watts),
+                series("GPU", "#d62728", |s| s.gpu_power_watts.map(f64::from)),
+                series("Battery", "#2ca02c", |s| s.power.batteries.first().and_then(|b| b.power_w)),
+            ],
+        },
+        Chart {
+            title: "Battery",
+            unit: "%",
+            range: Some((0.0, 100.0)),
+            series: vec![series("Charge", "#2ca02c", |s| {
+                s.power.batteries.first().and_then(|b| b.capacity_percent).map(f64::from)
+            })],
+        },
+    ]
+}
+
+/// One `<svg>` with gridlines, axis labels and a polyline per series. Gaps in a series
+/// break its line instead of being drawn across.
+fn render_chart(chart: &Chart, offsets: &[f64]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<h3>{} ({})</h3>", chart.title, escape(chart.unit));
+
+    let series: Vec<(&Series, Points)> = chart
+        .series
+        .iter()
+        .filter(|s| s.values.iter().any(Option::is_some))
+        .map(|s| (s, downsample(offsets, &s.values)))
+        .collect();
+    if series.is_empty() {
+        let _ = writeln!(out, "<p class=\"empty\">Not available on this device.</p>");
+        return out;
+    }
+
+    let values = series.iter().flat_map(|(_, points)| points.iter().filter_map(|(_, v)| *v));
+    let (low, high) = chart.range.unwrap_or_else(|| fitted_range(values));
+    let duration = offsets.last().copied().unwrap_or(0.0).max(1.0);
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let x = |secs: f64| MARGIN_LEFT + secs / duration * plot_width;
+    let y = |value: f64| MARGIN_TOP + (1.0 - (value - low) / (high - low)) * plot_height;
+
+    let _ = writeln!(
+        out,
+        "<svg viewBox=\"0 0 {} {}\" role=\"img\" aria-label=\"{}\">",
+        CHART_WIDTH,
+        CHART_HEIGHT,
+        escape(chart.title)
+    );
+    for step in 0..=4 {
+        let value = low + (high - low) * step as f64 / 4.0;
+        let _ = writeln!(
+            out,
+            "<line class=\"grid\" x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/><text class=\"ylabel\" x=\"{:.1}\" y=\"{:.1}\">{:.0}</text>",
+            MARGIN_LEFT,
+            y(value),
+            CHART_WIDTH - MARGIN_RIGHT,
+            y(value),
+            MARGIN_LEFT - 6.0,
+            y(value) + 4.0,
+            value
+        );
+    }
+    for step in 0..=4 {
+        let secs = duration * step as f64 / 4.0;
+        let _ = writeln!(
+            out,
+            "<text class=\"xlabel\" x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            x(secs),
+            CHART_HEIGHT - 8.0,
+            format_offset(secs)
+        );
+    }
+
+    for (s, points) in &series {
+        for segment in points.split(|(_, v)| v.is_none()).filter(|segment| !segment.is_empty()) {
+            let coords: Vec<(f64, f64)> = segment
+                .iter()
+                .filter_map(|(secs, v)| v.map(|v| (x(*secs), y(v.clamp(low, high)))))
+                .collect();
+            // A lone point has no line to draw, so it gets a dot
+            if let [(cx, cy)] = coords[..] {
+                let _ = writeln!(out, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"2.5\" fill=\"{}\"/>", cx, cy, s.color);
+            } else {
+                let points: Vec<String> = coords.iter().map(|(px, py)| format!("{:.1},{:.1}", px, py)).collect();
+                let _ = writeln!(
+                    out,
+                    "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>",
+                    s.color,
+                    points.join(" ")
+                );
+            }
+        }
+    }
+    let _ = writeln!(out, "</svg>");
+
+    let _ = write!(out, "<p class=\"legend\">");
+    for (s, _) in &series {
+        let _ = write!(out, "<span style=\"color:{}\">&#9632;</span> {} ", s.color, escape(s.label));
+    }
+    let _ = writeln!(out, "</p>");
+    out
+}
+
+/// Averages runs of samples so a series has at most `MAX_POINTS` points. A bucket with no
+/// values stays a gap.
+fn downsample(offsets: &[f64], values: &[Option<f64>]) -> Points {
+    let bucket = values.len().div_ceil(MAX_POINTS).max(1);
+    offsets
+        .chunks(bucket)
+        .zip(values.chunks(bucket))
+        .map(|(secs, chunk)| {
+            let present: Vec<f64> = chunk.iter().flatten().copied().collect();
+            let average = if present.is_empty() { None } else { Some(present.iter().sum::<f64>() / present.len() as f64) };
+            (secs[0], average)
+        })
+        .collect()
+}
+
+/// The data's range with a little headroom, never flat.
+fn fitted_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), v| (min.min(v), max.max(v)));
+    let pad = ((max - min) * 0.1).max(1.0);
+    ((min - pad).max(0.0).floor(), (max + pad).ceil())
+}
+
+/// Offset from the session start, e.g. "0:00", "12:30", "1:05:00".
+fn format_offset(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn escape(raw: &str) -> String {
+    raw.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:800px;margin:2em auto;padding:0 1em;color:#222}\
+h1{font-size:1.5em}h2{font-size:1.2em;margin-top:1.5em;border-bottom:1px solid #ccc}h3{font-size:1em;margin:1.2em 0 .3em}\
+table{border-collapse:collapse}th{text-align:left;font-weight:normal;color:#555;padding:2px 16px 2px 0}\
+table.summary td{text-align:right;font-variant-numeric:tabular-nums}\
+svg{width:100%;height:auto;background:#fafafa;border:1px solid #eee}\
+.grid{stroke:#ddd;stroke-width:1}.ylabel{font-size:11px;fill:#666;text-anchor:end}.xlabel{font-size:11px;fill:#666;text-anchor:middle}\
+.legend{font-size:.9em;margin:.3em 0}.empty{color:#888;font-style:italic}.footer{margin-top:2em;font-size:.8em;color:#888}";
@@ -0,0 +1,329 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use crate::paths::{config_dir, ensure_logs_dir, log_kind, migrated_this_run, LogKind};
+#[cfg(feature = "sqlite")]
+use crate::store::{TelemetryStore, DB_FILE};
+
+/// Sessions written this recently may still be open (a running `--record`), so they're left alone.
+const ACTIVE_GRACE: Duration = Duration::from_secs(5 * 60);
+
+const SECS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_str(name: &str) -> Option<Compression> {
+        match name {
+            "none" => Some(Compression::None),
+            "gzip" | "gz" => Some(Compression::Gzip),
+            "zstd" | "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+}
+
+/// Read from `retention.json` in the config directory; fields left out keep their defaults,
+/// and `null` turns a limit off. No limit is set by default, so nothing is ever deleted
+/// unless the user configured it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub max_files: Option<usize>,
+    pub max_age_days: Option<u64>,
+    pub max_total_mb: Option<u64>,
+    pub compress_after_days: Option<u64>, // Sessions untouched this long get compressed
+    pub compression: Compression,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_files: None,
+            max_age_days: None,
+            max_total_mb: None,
+            compress_after_days: Some(1),
+            compression: Compression::Zstd,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PruneReport {
+    pub deleted: Vec<PathBuf>,
+    pub compressed: Vec<PathBuf>,
+    pub freed_bytes: u64,
+    pub kept_files: usize,
+    pub kept_bytes: u64,
+    pub store_samples_deleted: usize,
+    pub store_bytes: u64, // telemetry.db with its WAL; not counted toward `max_total_mb`
+}
+
+impl PruneReport {
+    pub fn is_empty(&self) -> bool {
+        self.deleted.is_empty() && self.compressed.is_empty() && self.store_samples_deleted == 0
+    }
+}
+
+struct LogFile {
+    path: PathBuf,
+    timestamp: u64, // From the file name, so copies and moves don't reset a file's age
+    modified: SystemTime,
+    bytes: u64,
+    is_session: bool,
+    compressed: bool,
+}
+
+pub fn policy_path() -> PathBuf {
+    config_dir().join("retention.json")
+}
+
+/// The configured policy, or the defaults if there's no config file.
+pub fn load_policy() -> RetentionPolicy {
+    let path = policy_path();
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(_) => return RetentionPolicy::default(),
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        eprintln!("[Logs] Ignoring {}: {}", path.display(), e);
+        RetentionPolicy::default()
+    })
+}
+
+/// Applies the configured policy after a log or session is written, reporting only if it did something.
+pub fn enforce_default() {
+    if migrated_this_run() {
+        println!("[Logs] Skipping retention this run, since files were just moved from ./logs");
+        return;
+    }
+    let result = ensure_logs_dir().and_then(|dir| apply_retention(&dir, &load_policy(), false));
+    match result {
+        Ok(report) if !report.is_empty() => println!(
+            "[Logs] Retention: compressed {}, deleted {} ({:.1} MB freed), {} stored samples removed",
+            report.compressed.len(),
+            report.deleted.len(),
+            report.freed_bytes as f64 / 1e6,
+            report.store_samples_deleted
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("[Logs] Retention failed: {}", e),
+    }
+}
+
+/// Deletes the oldest snapshot and session files past the age, count or size limits, then
+/// compresses the sessions that are left and older than `compress_after_days`. Leftover
+/// `.partial` files from an interrupted compression are always removed first.
+/// The telemetry store only follows the age limit, and its size isn't part of `max_total_mb`:
+/// deleting files can't shrink it. With `dry_run`, only reports what it would do.
+pub fn apply_retention(dir: &Path, policy: &RetentionPolicy, dry_run: bool) -> io::Result<PruneReport> {
+    let now = SystemTime::now();
+    let now_secs = now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut files = list_log_files(dir)?;
+    files.sort_by_key(|f| f.timestamp);
+
+    // Only an uncompressed session can still be open for writing
+    let active = |f: &LogFile| {
+        f.is_session && !f.compressed && now.duration_since(f.modified).map_or(true, |age| age < ACTIVE_GRACE)
+    };
+    let mut report = PruneReport::default();
+    remove_stale_partials(dir, now, dry_run, &mut report)?;
+    let mut keep = Vec::new();
+    let mut remaining_files = files.len();
+    let mut remaining_bytes: u64 = files.iter().map(|f| f.bytes).sum();
+    let max_bytes = policy.max_total_mb.map(|mb| mb * 1_000_000);
+
+    // Oldest first, so count and size limits drop the oldest files
+    for file in files {
+        let too_old = policy
+            .max_age_days
+            .is_some_and(|days| now_secs.saturating_sub(file.timestamp) > days * SECS_PER_DAY);
+        let too_many = policy.max_files.is_some_and(|max| remaining_files > max);
+        let too_big = max_bytes.is_some_and(|max| remaining_bytes > max);
+
+        if (too_old || too_many || too_big) && !active(&file) {
+            if !dry_run {
+                fs::remove_file(&file.path)?;
+            }
+            remaining_files -= 1;
+            remaining_bytes -= file.bytes;
+            report.freed_bytes += file.bytes;
+            report.deleted.push(file.path);
+        } else {
+            keep.push(file);
+        }
+    }
+
+    if let (Some(days), Some(ext)) = (policy.compress_after_days, policy.compression.extension()) {
+        for file in keep.iter_mut() {
+            let idle = now.duration_since(file.modified).unwrap_or_default();
+            if !file.is_session || file.compressed || active(file) || idle.as_secs() < days * SECS_PER_DAY {
+                continue;
+            }
+            let target = PathBuf::from(format!("{}.{}", file.path.display(), ext));
+            if !dry_run {
+                let written = compress_file(&file.path, &target, policy.compression, file.modified)?;
+                report.freed_bytes += file.bytes.saturating_sub(written);
+                file.bytes = written;
+            }
+            report.compressed.push(file.path.clone());
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(days) = policy.max_age_days {
+        let cutoff = now_secs.saturating_sub(days * SECS_PER_DAY);
+        let db = dir.join(DB_FILE);
+        if db.exists() {
+            let store = TelemetryStore::open(&db).map_err(io::Error::other)?;
+            report.store_samples_deleted = if dry_run {
+                store.count_before(cutoff)
+            } else {
+                store.delete_before(cutoff)
+            }
+            .map_err(io::Error::other)?;
+        }
+    }
+
+    report.store_bytes = ["telemetry.db", "telemetry.db-wal"]
+        .iter()
+        .filter_map(|name| fs::metadata(dir.join(name)).ok())
+        .map(|meta| meta.len())
+        .sum();
+    report.kept_files = keep.len();
+    report.kept_bytes = keep.iter().map(|f| f.bytes).sum();
+    Ok(report)
+}
+
+/// A compression killed mid-write leaves `session_<secs>.jsonl.partial` next to the intact
+/// original. Ones older than the grace period can't belong to a compression still running.
+fn remove_stale_partials(dir: &Path, now: SystemTime, dry_run: bool, report: &mut PruneReport) -> io::Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_partial = name
+            .strip_suffix(".partial")
+            .is_some_and(|base| log_kind(base) == Some(LogKind::Session) && base.ends_with(".jsonl"));
+        let meta = match entry.metadata() {
+            Ok(meta) if is_partial && meta.is_file() => meta,
+            _ => continue,
+        };
+        let modified = meta.modified().unwrap_or(UNIX_EPOCH);
+        if now.duration_since(modified).is_ok_and(|age| age >= ACTIVE_GRACE) {
+            if !dry_run {
+                fs::remove_file(entry.path())?;
+            }
+            report.freed_bytes += meta.len();
+            report.deleted.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn list_log_files(dir: &Path) -> io::Result<Vec<LogFile>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_session = match log_kind(&name) {
+            Some(LogKind::Session) => true,
+            Some(LogKind::Snapshot) => false,
+            // The store is pruned by row, never as a file
+            Some(LogKind::Store) | None => continue,
+        };
+        let rest = name.trim_start_matches("session_").trim_start_matches("system_log_");
+        let meta = match entry.metadata() {
+            Ok(meta) if meta.is_file() => meta,
+            _ => continue,
+        };
+        let modified = meta.modified().unwrap_or(UNIX_EPOCH);
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let timestamp = digits
+            .parse()
+            .unwrap_or_else(|_| modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0));
+
+        files.push(LogFile {
+            path: entry.path(),
+            timestamp,
+            modified,
+            bytes: meta.len(),
+            is_session,
+            compressed: name.ends_with(".gz") || name.ends_with(".zst"),
+        });
+    }
+    Ok(files)
+}
+
+/// Writes `target` next to `source` and removes the source. The compressed file keeps the
+/// source's mtime so age checks stay the same. Returns the compressed size.
+fn compress_file(source: &Path, target: &Path, compression: Compression, modified: SystemTime) -> io::Result<u64> {
+    // Write under a temporary name so a crash never leaves a truncated archive that looks complete
+    let partial = target.with_extension("partial");
+    let mut input = BufReader::new(File::open(source)?);
+    let output = BufWriter::new(File::create(&partial)?);
+    let output = match compression {
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(output, 0)?;
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?
+        }
+        Compression::None => return Ok(fs::metadata(source)?.len()),
+    };
+    let file = output.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    file.set_modified(modified)?;
+    drop(file);
+
+    fs::rename(&partial, target)?;
+    fs::remove_file(source)?;
+    Ok(fs::metadata(target)?.len())
+}
+
+/// Human-readable summary for `logs prune`.
+pub fn print_report(report: &PruneReport, dry_run: bool) {
+    let verb = |done: &'static str, planned: &'static str| if dry_run { planned } else { done };
+    for path in &report.deleted {
+        println!("[Logs] {} {}", verb("Deleted", "Would delete"), path.display());
+    }
+    for path in &report.compressed {
+        println!("[Logs] {} {}", verb("Compressed", "Would compress"), path.display());
+    }
+    println!(
+        "[Logs] {} {} files, {} {}; {} files ({:.1} MB) kept",
+        verb("Deleted", "Would delete"),
+        report.deleted.len(),
+        verb("compressed", "compress"),
+        report.compressed.len(),
+        report.kept_files,
+        report.kept_bytes as f64 / 1e6
+    );
+    if report.store_bytes > 0 {
+        println!(
+            "[Logs] Telemetry store: {:.1} MB (trimmed by max_age_days only, not counted toward max_total_mb)",
+            report.store_bytes as f64 / 1e6
+        );
+    }
+    if report.store_samples_deleted > 0 {
+        println!("[Logs] {} {} samples from the telemetry store", verb("Removed", "Would remove"), report.store_samples_deleted);
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
+
+use crate::gpu_procs::{compute_gpu_usage, sample_fdinfo, FdinfoSample, GpuProcess};
+use crate::hardware::{collect_gpu_stats, read_apu_socket_power, select_gpu, GpuCard, GpuStats};
+use crate::rapl::{package_power_between, read_package_energy, RaplSample};
+use crate::cpufreq::{read_cpu_policies, read_throttle_status, CpuPolicy, ThrottleStatus};
+use crate::cpu::{per_core_usage, read_proc_stat, usage_between, CpuUsage, ProcStat};
+use crate::memory::{read_meminfo, read_zram_devices, MemoryStats, ZramDevice, BYTES_PER_GB};
+use crate::power::{read_power_supplies, PowerAverager, PowerSupplies};
+use crate::thermal::{read_thermal_zones, ThermalZone};
+use crate::storage::{disk_usage_between, read_diskstats, DiskSample, DiskUsage};
+use crate::network::{network_usage_between, read_net_dev, NetSample, NetworkUsage};
+use crate::processes::{compute_process_usage, sample_processes, ProcSample, ProcessInfo};
+use crate::pressure::{read_loadavg, read_pressure, LoadAverage, PressureStats};
+
+/// Version of the serialized `Snapshot` layout. Bump it whenever a field is renamed,
+/// removed or changes units; adding fields is backwards compatible.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One point-in-time reading of the system. This is also the record the logger writes,
+/// so the serialized field names are the JSON log schema. Fields missing from an older
+/// record deserialize to their defaults; see `schema` for the upgrades that need more than that.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    pub schema_version: u32,
+    pub timestamp: u64,
+    pub uptime: u64,
+    pub memory_used_gb: f64,  // total - available, in GB (10^9 bytes)
+    pub memory_total_gb: f64,
+    pub memory: Option<MemoryStats>,
+    pub zram: Vec<ZramDevice>,
+    pub power: PowerSupplies,
+    pub disks: Vec<DiskUsage>,
+    pub network: Vec<NetworkUsage>,
+    pub accurate_cpu_total: Option<f64>,
+    pub sysinfo_cpu_total: Option<f32>,
+    pub per_core: Vec<f32>, // Positional: online cores in CPU-number order, so offline cores leave no gap
+    pub cpu_breakdown: Option<CpuUsage>,
+    pub cpu_policies: Vec<CpuPolicy>,
+    pub throttle: ThrottleStatus,
+    pub thermal_zones: Vec<ThermalZone>,
+    pub load_average: Option<LoadAverage>,
+    pub pressure: PressureStats,
+
+    // gpu metrics
+    pub gpu_util_percent: Option<u32>,
+    pub gpu_temp_celsius: Option<f32>,
+    pub gpu_core_clock_mhz: Option<u32>,
+    pub gpu_mem_clock_mhz: Option<u32>,
+    pub gpu_power_watts: Option<f32>,
+
+    // RAPL package energy delta, or the APU socket power from gpu_metrics
+    pub package_power_watts: Option<f64>,
+
+    // Live-only detail that isn't part of the log schema
+    #[serde(skip)]
+    pub per_core_breakdown: Vec<(u32, CpuUsage)>, // (N from cpuN, usage)
+    #[serde(skip)]
+    pub gpu_card: Option<GpuCard>,
+    #[serde(skip)]
+    pub gpu: GpuStats,
+    #[serde(skip)]
+    pub gpu_processes: Vec<GpuProcess>,
+    #[serde(skip)]
+    pub processes: Vec<ProcessInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SamplerConfig {
+    pub interval: Duration,
+    pub capacity: usize,     // Number of snapshots kept in the ring buffer
+    pub gpu: Option<String>, // Same selector as `--gpu`
+}
+
+impl Default for SamplerConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(1000),
+            capacity: 600,
+            gpu: None,
+        }
+    }
+}
+
+/// The first snapshot is taken after this long (or the interval, if shorter) so one-shot
+/// CLI commands don't have to wait a full interval for their CPU deltas.
+const PRIME_DELAY: Duration = Duration::from_millis(250);
+
+struct Shared {
+    history: Mutex<History>,
+    ready: Condvar,
+    gpu: Mutex<Option<String>>,
+}
+
+struct History {
+    snapshots: VecDeque<Snapshot>,
+    taken: u64, // Snapshots taken since start, so consumers can tell a new one from the last they saw
+}
+
+/// Handle to the background sampling thread.
+pub struct Sampler {
+    shared: Arc<Shared>,
+    interval: Duration,
+}
+
+static SAMPLER: OnceLock<Sampler> = OnceLock::new();
+
+/// Starts the shared sampler thread. Later calls return the running sampler and only
+/// update its GPU selection.
+pub fn start(config: SamplerConfig) -> &'static Sampler {
+    if let Some(sampler) = SAMPLER.get() {
+        sampler.set_gpu(config.gpu.as_deref());
+        return sampler;
+    }
+    SAMPLER.get_or_init(|| Sampler::spawn(config))
+}
+
+/// The shared sampler, started with the default configuration if nothing started it yet.
+pub fn shared() -> &'static Sampler {
+    SAMPLER.get_or_init(|| Sampler::spawn(SamplerConfig::default()))
+}
+
+impl Sampler {
+    fn spawn(config: SamplerConfig) -> Sampler {
+        let shared = Arc::new(Shared {
+            history: Mutex::new(History {
+                snapshots: VecDeque::with_capacity(config.capacity),
+                taken: 0,
+            }),
+            ready: Condvar::new(),
+            gpu: Mutex::new(config.gpu.clone()),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        let interval = config.interval;
+        let capacity = config.capacity.max(1);
+        thread::spawn(move || {
+            let mut state = SamplerState::new();
+            thread::sleep(interval.min(PRIME_DELAY));
+            // Schedule against fixed deadlines so the time spent sampling
+            // doesn't stretch the period; if a tick overruns, start afresh.
+            let mut next_deadline = Instant::now();
+            loop {
+                let gpu = thread_shared.gpu.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                let snapshot = state.sample(gpu.as_deref());
+
+                let mut history = thread_shared.history.lock().unwrap_or_else(|e| e.into_inner());
+                if history.snapshots.len() == capacity {
+                    history.snapshots.pop_front();
+                }
+                history.snapshots.push_back(snapshot);
+                history.taken += 1;
+                drop(history);
+                thread_shared.ready.notify_all();
+
+                next_deadline += interval;
+                let now = Instant::now();
+                if next_deadline > now {
+                    thread::sleep(next_deadline - now);
+                } else {
+                    next_deadline = now;
+                }
+            }
+        });
+
+        Sampler { shared, interval }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn set_gpu(&self, selector: Option<&str>) {
+        *self.shared.gpu.lock().unwrap_or_else(|e| e.into_inner()) = selector.map(|s| s.to_string());
+    }
+
+    /// The most recent snapshot, if the first one has been taken.
+    pub fn latest(&self) -> Option<Snapshot> {
+        self.shared.history.lock().unwrap_or_else(|e| e.into_inner()).snapshots.back().cloned()
+    }
+
+    /// Blocks until at least one snapshot exists and returns the newest.
+    pub fn wait_latest(&self) -> Snapshot {
+        let mut history = self.shared.history.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(snapshot) = history.snapshots.back() {
+                return snapshot.clone();
+            }
+            history = self.shared.ready.wait(history).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Waits up to `timeout` for a snapshot newer than number `seen` and returns it with its
+    /// number. Pass 0 to get the first snapshot. Returns `None` on timeout.
+    pub fn wait_next(&self, seen: u64, timeout: Duration) -> Option<(u64, Snapshot)> {
+        let history = self.shared.history.lock().unwrap_or_else(|e| e.into_inner());
+        let (history, _) = self
+            .shared
+            .ready
+            .wait_timeout_while(history, timeout, |h| h.taken <= seen)
+            .unwrap_or_else(|e| e.into_inner());
+        if history.taken <= seen {
+            return None;
+        }
+        history.snapshots.back().map(|s| (history.taken, s.clone()))
+    }
+
+    /// Every snapshot in the ring buffer, oldest first.
+    pub fn history(&self) -> Vec<Snapshot> {
+        self.shared.history.lock().unwrap_or_else(|e| e.into_inner()).snapshots.iter().cloned().collect()
+    }
+}
+
+/// Counters carried from one sample to the next so every value is a delta over one interval.
+struct SamplerState {
+    sys: System,
+    prev_stat: Option<ProcStat>,
+    prev_fdinfo: FdinfoSample,
+    prev_throttle: Option<ThrottleStatus>,
+    power_average: PowerAverager,
+    prev_disks: Option<DiskSample>,
+    prev_net: Option<NetSample>,
+    prev_procs: ProcSample,
+    prev_rapl: Option<RaplSample>,
+}
+
+impl SamplerState {
+    fn new() -> Self {
+        let refresh = RefreshKind::new()
+            .with_cpu(CpuRefreshKind::everything())
+            .with_memory(MemoryRefreshKind::new());
+        let mut sys = System::new_with_specifics(refresh);
+        sys.refresh_cpu();
+
+        Self {
+            sys,
+            prev_stat: read_proc_stat(),
+            prev_fdinfo: sample_fdinfo(),
+            prev_throttle: None,
+            power_average: PowerAverager::default(),
+            prev_disks: read_diskstats(),
+            prev_net: read_net_dev(),
+            prev_procs: sample_processes(),
+            prev_rapl: read_package_energy(),
+        }
+    }
+
+    fn sample(&mut self, gpu: Option<&str>) -> Snapshot {
+        self.sys.refresh_cpu();
+        self.sys.refresh_memory();
+
+        //time
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let uptime = System::uptime();
+
+        //memory: /proc/meminfo, with sysinfo (bytes) as the fallback
+        let memory = read_meminfo();
+        let (used_bytes, total_bytes) = match &memory {
+            Some(m) => (m.used_bytes(), m.total_bytes),
+            None => (self.sys.used_memory(), self.sys.total_memory()),
+        };
+        let total_memory = total_bytes as f64 / BYTES_PER_GB;
+        let used_memory = used_bytes as f64 / BYTES_PER_GB;
+        let zram = read_zram_devices();
+
+        //battery
+        let mut power = read_power_supplies();
+        self.power_average.update(&mut power);
+
+        //CPU
+        let stat = read_proc_stat();
+        let (cpu_breakdown, per_core_breakdown) = match (&self.prev_stat, &stat) {
+            (Some(prev), Some(curr)) => (
+                Some(usage_between(&prev.total, &curr.total)),
+                per_core_usage(prev, curr),
+            ),
+            _ => (None, Vec::new()),
+        };
+        self.prev_stat = stat;
+
+        let accurate_cpu_total = cpu_breakdown.as_ref().map(|b| b.busy_percent);
+        let sysinfo_cpu_total = Some(self.sys.global_cpu_info().cpu_usage());
+        let per_core: Vec<f32> = per_core_breakdown.iter().map(|(_, c)| c.busy_percent as f32).collect();
+
+        let cpu_policies = read_cpu_policies();
+
+        //storage
+        let disk_sample = read_diskstats();
+        let disks = match (&self.prev_disks, &disk_sample) {
+            (Some(prev), Some(curr)) => disk_usage_between(prev, curr),
+            _ => Vec::new(),
+        };
+        self.prev_disks = disk_sample;
+
+        //network
+        let net_sample = read_net_dev();
+        let network = match (&self.prev_net, &net_sample) {
+            (Some(prev), Some(curr)) => network_usage_between(prev, curr),
+            _ => Vec::new(),
+        };
+        self.prev_net = net_sample;
+
+        //contention
+        let load_average = read_loadavg();
+        let pressure = read_pressure();
+
+        //GPU
+        let gpu_card = select_gpu(gpu);
+        let gpu_stats = gpu_card
+            .as_ref()
+            .and_then(|c| collect_gpu_stats(c).ok())
+            .unwrap_or_default();
+        let fdinfo = sample_fdinfo();
+        let gpu_processes = compute_gpu_usage(&self.prev_fdinfo, &fdinfo);
+        self.prev_fdinfo = fdinfo;
+
+        //processes
+        let proc_sample = sample_processes();
+        let processes = compute_process_usage(&self.prev_procs, &proc_sample, &gpu_processes);
+        self.prev_procs = proc_sample;
+
+        // Throttling: a raised GPU bitmask, or thermal_throttle counters that moved since the last sample
+        let mut throttle = read_throttle_status(gpu_card.as_ref());
+        if let Some(prev) = &self.prev_throttle {
+            let moved = |a: Option<u64>, b: Option<u64>| matches!((a, b), (Some(a), Some(b)) if b > a);
+            throttle.throttled |= moved(prev.core_throttle_count, throttle.core_throttle_count)
+                || moved(prev.package_throttle_count, throttle.package_throttle_count);
+        }
+        self.prev_throttle = Some(throttle.clone());
+        let thermal_zones = read_thermal_zones();
+
+        //package power
+        let rapl = read_package_energy();
+        let package_power_watts = match (&self.prev_rapl, &rapl) {
+            (Some(prev), Some(curr)) => package_power_between(prev, curr),
+            _ => None,
+        }
+        .or_else(|| gpu_card.as_ref().and_then(read_apu_socket_power));
+        self.prev_rapl = rapl;
+
+        Snapshot {
+            schema_version: SCHEMA_VERSION,
+            timestamp,
+            uptime,
+            memory_used_gb: used_memory,
+            memory_total_gb: total_memory,
+            memory,
+            zram,
+            power,
+            disks,
+            network,
+            accurate_cpu_total,
+            sysinfo_cpu_total,
+            per_core,
+            cpu_breakdown,
+            cpu_policies,
+            throttle,
+            thermal_zones,
+            load_average,
+            pressure,
+
+            gpu_util_percent: gpu_stats.gpu_util_percent,
+            gpu_temp_celsius: gpu_stats.temperature_c,
+            gpu_core_clock_mhz: gpu_stats.core_clock_mhz,
+            gpu_mem_clock_mhz: gpu_stats.memory_clock_mhz,
+            gpu_power_watts: gpu_stats.power_watts,
+            package_power_watts,
+
+            per_core_breakdown,
+            gpu_card,
+            gpu: gpu_stats,
+            gpu_processes,
+            processes,
+        }
+    }
+}
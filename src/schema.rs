@@ -0,0 +1,214 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use serde_json::{Map, Value};
+
+use crate::sampler::{Snapshot, SCHEMA_VERSION};
+use crate::session::{SessionEnd, SessionInfo};
+
+/// sysinfo's byte counts divided by 2^20: the unit v0 logs call "GB".
+const BYTES_PER_MIB: f64 = 1_048_576.0;
+
+/// Whatever a log file turned out to hold.
+#[derive(Debug, Clone)]
+pub enum LogFile {
+    Snapshot(Box<Snapshot>),
+    Session(Box<SessionLog>),
+}
+
+/// A `--record` session file. Header and footer are missing if the file was cut short.
+#[derive(Debug, Clone, Default)]
+pub struct SessionLog {
+    pub info: Option<SessionInfo>,
+    pub samples: Vec<Snapshot>,
+    pub end: Option<SessionEnd>,
+    pub skipped_lines: usize, // Lines that didn't parse, e.g. half a line after a crash
+}
+
+/// Deserializes a snapshot of any known schema version into the current `Snapshot`.
+pub fn upgrade_snapshot(value: Value) -> Result<Snapshot, String> {
+    let mut record = match value {
+        Value::Object(record) => record,
+        _ => return Err("snapshot is not a JSON object".to_string()),
+    };
+
+    let mut version = record_version(&record);
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "written by a newer version (schema {}, this build reads up to {})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    // One step per version, so each upgrade only has to know its own change
+    while version < SCHEMA_VERSION {
+        match version {
+            0 => upgrade_v0(&mut record),
+            _ => unreachable!("no upgrade from schema {}", version),
+        }
+        version += 1;
+    }
+    // Also stamps unversioned v1 records, so a re-serialized snapshot isn't read back as v0
+    record.insert("schema_version".to_string(), Value::from(version));
+
+    serde_json::from_value(Value::Object(record)).map_err(|e| e.to_string())
+}
+
+fn record_version(record: &Map<String, Value>) -> u32 {
+    match record.get("schema_version").and_then(Value::as_u64) {
+        Some(version) => version as u32,
+        // Unversioned records that already carry the meminfo breakdown were written after the
+        // switch to decimal GB, which predates the version field; they only lack the field
+        None if record.contains_key("memory") => 1,
+        None => 0,
+    }
+}
+
+/// v0 (the original `LogEntry`) stored sysinfo memory in MiB under the `_gb` names.
+fn upgrade_v0(record: &mut Map<String, Value>) {
+    for key in ["memory_used_gb", "memory_total_gb"] {
+        if let Some(mib) = record.get(key).and_then(Value::as_f64) {
+            record.insert(key.to_string(), Value::from(mib * BYTES_PER_MIB / 1e9));
+        }
+    }
+}
+
+/// Reads a `system_log_*.json` snapshot or a `session_*.jsonl` recording, plain or
+/// compressed by retention (`.gz`, `.zst`), upgrading every record to the current schema.
+pub fn read_log_file(path: &Path) -> Result<LogFile, String> {
+    let reader = open_log(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    if name.contains(".jsonl") {
+        return read_session(reader).map(|log| LogFile::Session(Box::new(log))).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    let value: Value = serde_json::from_reader(reader).map_err(|e| format!("{}: {}", path.display(), e))?;
+    upgrade_snapshot(value)
+        .map(|snapshot| LogFile::Snapshot(Box::new(snapshot)))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn open_log(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    let extension = path.extension().and_then(|e| e.to_str());
+    Ok(match extension {
+        Some("gz") => Box::new(BufReader::new(flate2::read::GzDecoder::new(file))),
+        Some("zst") => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        _ => Box::new(BufReader::new(file)),
+    })
+}
+
+fn read_session(reader: Box<dyn BufRead>) -> Result<SessionLog, String> {
+    let mut log = SessionLog::default();
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut value: Value = match serde_json::from_str(&line) {
+            Ok(value) => value,
+            Err(_) => {
+                log.skipped_lines += 1;
+                continue;
+            }
+        };
+
+        if let Some(info) = value.get_mut("session").map(Value::take) {
+            let info: SessionInfo = serde_json::from_value(info).map_err(|e| format!("session header: {}", e))?;
+            if info.schema_version > SCHEMA_VERSION {
+                return Err(format!(
+                    "written by a newer version (schema {}, this build reads up to {})",
+                    info.schema_version, SCHEMA_VERSION
+                ));
+            }
+            log.info = Some(info);
+        } else if let Some(end) = value.get_mut("session_end").map(Value::take) {
+            log.end = Some(serde_json::from_value(end).map_err(|e| format!("session footer: {}", e))?);
+        } else {
+            match upgrade_snapshot(value) {
+                Ok(snapshot) => log.samples.push(snapshot),
+                Err(_) => log.skipped_lines += 1,
+            }
+        }
+    }
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::memory::MemoryStats;
+
+    /// A log written by the original `LogEntry`: memory in MiB under the `_gb` names.
+    const V0_LOG: &str = include_str!("../logs/system_log_1747159187.json");
+
+    fn session(lines: &[String]) -> Result<SessionLog, String> {
+        read_session(Box::new(io::Cursor::new(lines.join("\n"))))
+    }
+
+    fn header(schema_version: u32) -> String {
+        json!({ "session": {
+            "schema_version": schema_version,
+            "started_at": 1760000000,
+            "interval_ms": 1000,
+            "profile": "balance",
+            "game": null,
+            "device_model": null,
+            "hostname": null,
+        } })
+        .to_string()
+    }
+
+    #[test]
+    fn upgrades_v0_memory_from_mib_to_gb() {
+        let snapshot = upgrade_snapshot(serde_json::from_str(V0_LOG).unwrap()).unwrap();
+        assert_eq!(snapshot.schema_version, SCHEMA_VERSION);
+        assert_eq!(snapshot.timestamp, 1747159187);
+        // 10112.7578125 MiB and 31412.0390625 MiB
+        assert!((snapshot.memory_used_gb - 10.604).abs() < 0.001, "{}", snapshot.memory_used_gb);
+        assert!((snapshot.memory_total_gb - 32.938).abs() < 0.001, "{}", snapshot.memory_total_gb);
+        assert_eq!(snapshot.per_core.len(), 15);
+        assert!(snapshot.memory.is_none());
+    }
+
+    #[test]
+    fn unversioned_record_with_memory_is_v1() {
+        let record = json!({
+            "timestamp": 1760000000,
+            "memory_used_gb": 6.42,
+            "memory_total_gb": 15.9,
+            "memory": MemoryStats::default(),
+        });
+        let snapshot = upgrade_snapshot(record).unwrap();
+        assert_eq!(snapshot.schema_version, SCHEMA_VERSION);
+        assert_eq!(snapshot.memory_used_gb, 6.42);
+        assert_eq!(snapshot.memory_total_gb, 15.9);
+        assert!(snapshot.memory.is_some());
+    }
+
+    #[test]
+    fn rejects_records_from_a_newer_schema() {
+        let record = json!({ "schema_version": SCHEMA_VERSION + 1, "timestamp": 1760000000 });
+        let err = upgrade_snapshot(record).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+
+        let err = session(&[header(SCHEMA_VERSION + 1)]).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+    }
+
+    #[test]
+    fn counts_a_truncated_session_line_as_skipped() {
+        let sample = json!({ "schema_version": SCHEMA_VERSION, "timestamp": 1760000001, "memory_used_gb": 3.5 });
+        let sample = sample.to_string();
+        // A crash mid-write leaves the last line cut short and no session_end footer
+        let truncated = sample[..sample.len() / 2].to_string();
+        let log = session(&[header(SCHEMA_VERSION), sample.clone(), sample, truncated]).unwrap();
+
+        assert!(log.info.is_some());
+        assert_eq!(log.samples.len(), 2);
+        assert_eq!(log.samples[0].memory_used_gb, 3.5);
+        assert_eq!(log.skipped_lines, 1);
+        assert!(log.end.is_none());
+    }
+}
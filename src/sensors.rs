@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hardware::read_trimmed;
+
+/// The hwmon channel families we understand, named after their sysfs file prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorKind {
+    Temperature, // temp*: millidegrees Celsius
+    Fan,         // fan*: RPM
+    Voltage,     // in*: millivolts
+    Power,       // power*: microwatts
+    Current,     // curr*: milliamps
+}
+
+impl SensorKind {
+    fn from_prefix(prefix: &str) -> Option<SensorKind> {
+        match prefix {
+            "temp" => Some(SensorKind::Temperature),
+            "fan" => Some(SensorKind::Fan),
+            "in" => Some(SensorKind::Voltage),
+            "power" => Some(SensorKind::Power),
+            "curr" => Some(SensorKind::Current),
+            _ => None,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Power => "W",
+            SensorKind::Current => "A",
+        }
+    }
+
+    /// Divisor that converts the raw sysfs integer into `unit()`.
+    fn scale(&self) -> f64 {
+        match self {
+            SensorKind::Temperature => 1_000.0,
+            SensorKind::Fan => 1.0,
+            SensorKind::Voltage => 1_000.0,
+            SensorKind::Power => 1_000_000.0,
+            SensorKind::Current => 1_000.0,
+        }
+    }
+}
+
+/// A single channel such as `temp1` with its value and thresholds already converted to `kind.unit()`.
+#[derive(Debug, Clone)]
+pub struct SensorReading {
+    pub channel: String,       // e.g. "temp1"
+    pub label: Option<String>, // Contents of temp1_label, if the driver provides one
+    pub kind: SensorKind,
+    pub value: Option<f64>,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+impl SensorReading {
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.channel)
+    }
+}
+
+/// One /sys/class/hwmon/hwmonN device, e.g. "k10temp", "amdgpu" or "nvme".
+#[derive(Debug, Clone)]
+pub struct SensorChip {
+    pub hwmon: String, // e.g. "hwmon3"
+    pub name: String,  // Contents of the `name` file
+    pub path: PathBuf,
+    pub readings: Vec<SensorReading>,
+}
+
+/// Walks every hwmon device and reads all of its temp/fan/in/power/curr channels.
+pub fn enumerate_sensors() -> Vec<SensorChip> {
+    let mut chips = Vec::new();
+    let entries = match fs::read_dir("/sys/class/hwmon") {
+        Ok(entries) => entries,
+        Err(_) => return chips,
+    };
+
+    for entry in entries.flatten() {
+        let hwmon = entry.file_name().to_string_lossy().to_string();
+        if !hwmon.starts_with("hwmon") {
+            continue;
+        }
+        let path = entry.path();
+        let name = read_trimmed(&path.join("name")).unwrap_or_else(|| hwmon.clone());
+        let readings = read_channels(&path);
+        chips.push(SensorChip { hwmon, name, path, readings });
+    }
+
+    chips.sort_by_key(|c| c.hwmon.trim_start_matches("hwmon").parse::<u32>().unwrap_or(u32::MAX));
+    chips
+}
+
+fn read_channels(dir: &Path) -> Vec<SensorReading> {
+    let mut channels: Vec<(SensorKind, u32, String)> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let file = entry.file_name().to_string_lossy().to_string();
+            // Channel files look like "<prefix><N>_<attribute>", e.g. temp1_input or power1_average
+            let (channel, attribute) = match file.split_once('_') {
+                Some(parts) => parts,
+                None => continue,
+            };
+            if attribute != "input" && attribute != "average" {
+                continue;
+            }
+            let digits = channel.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            let prefix = &channel[..channel.len() - digits.len()];
+            let (kind, number) = match (SensorKind::from_prefix(prefix), digits.parse::<u32>()) {
+                (Some(kind), Ok(number)) => (kind, number),
+                _ => continue,
+            };
+            if !channels.iter().any(|(_, _, c)| c == channel) {
+                channels.push((kind, number, channel.to_string()));
+            }
+        }
+    }
+
+    channels.sort();
+    channels
+        .into_iter()
+        .map(|(kind, _, channel)| {
+            let scaled = |attribute: &str| {
+                read_trimmed(&dir.join(format!("{}_{}", channel, attribute)))
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .map(|v| v / kind.scale())
+            };
+            // Power sensors often only expose an averaged value
+            let value = scaled("input").or_else(|| scaled("average"));
+            SensorReading {
+                label: read_trimmed(&dir.join(format!("{}_label", channel))),
+                kind,
+                value,
+                max: scaled("max"),
+                crit: scaled("crit"),
+                channel,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+use crate::games::{detect_running_game, RunningGame};
+use crate::modes::active_profile;
+use crate::paths::prepare_logs_dir;
+use crate::retention::enforce_default;
+use crate::sampler::{self, SCHEMA_VERSION};
+#[cfg(feature = "sqlite")]
+use crate::store::TelemetryStore;
+
+/// How often to look for a game again when none was running at the start.
+const GAME_RESCAN: Duration = Duration::from_secs(30);
+
+/// First line of a session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub schema_version: u32,
+    pub started_at: u64,
+    pub interval_ms: u64,
+    pub profile: String,
+    pub game: Option<RunningGame>,
+    pub device_model: Option<String>, // DMI vendor + product, e.g. "Valve Jupiter"
+    pub hostname: Option<String>,
+}
+
+/// Last line of a cleanly closed session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEnd {
+    pub ended_at: u64,
+    pub samples: u64,
+    pub game: Option<RunningGame>, // Game seen during the session, if none was running at the start
+}
+
+/// Session files are JSON Lines: `{"session": ...}`, one snapshot per line, then `{"session_end": ...}`.
+#[derive(Serialize)]
+enum Marker<'a> {
+    #[serde(rename = "session")]
+    Start(&'a SessionInfo),
+    #[serde(rename = "session_end")]
+    End(&'a SessionEnd),
+}
+
+/// Appends every sampler snapshot to `session_<start>.jsonl` in the logs directory until Ctrl-C, SIGTERM
+/// or `duration` elapses, mirroring it into the telemetry store when that's built in.
+/// Returns the file path and the number of samples written.
+pub fn record_session(duration: Option<Duration>) -> io::Result<(PathBuf, u64)> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = Arc::clone(&stop);
+    if let Err(e) = ctrlc::set_handler(move || handler_stop.store(true, Ordering::SeqCst)) {
+        eprintln!("[Record] Could not install the Ctrl-C handler: {}", e);
+    }
+
+    let sampler = sampler::shared();
+    let first = sampler.wait_latest();
+    let info = SessionInfo {
+        schema_version: SCHEMA_VERSION,
+        started_at: first.timestamp,
+        interval_ms: sampler.interval().as_millis() as u64,
+        profile: active_profile(first.cpu_policies.first().and_then(|p| p.governor.as_deref())),
+        game: detect_running_game(),
+        device_model: device_model(),
+        hostname: hostname(),
+    };
+
+    let path = prepare_logs_dir()?.join(format!("session_{}.jsonl", info.started_at));
+    let mut out = BufWriter::new(File::create(&path)?);
+    write_line(&mut out, &Marker::Start(&info))?;
+    println!(
+        "[Record] Recording to {} ({}; Ctrl-C to stop)",
+        path.display(),
+        info.game.as_ref().map_or("no game detected".to_string(), |g| format!("game: {}", g.name))
+    );
+
+    // The JSON Lines file is the record of truth; a store failure only loses the indexed copy
+    #[cfg(feature = "sqlite")]
+    let mut store = match TelemetryStore::open_default().and_then(|store| {
+        let id = store.begin_session(&info)?;
+        Ok((store, id))
+    }) {
+        Ok(opened) => Some(opened),
+        Err(e) => {
+            eprintln!("[Record] Telemetry store unavailable, writing the file only: {}", e);
+            None
+        }
+    };
+
+    let started = Instant::now();
+    let mut last_scan = started;
+    let mut game = None;
+    let mut seen = 0;
+    let mut samples = 0;
+    while !stop.load(Ordering::SeqCst) && duration.is_none_or(|d| started.elapsed() < d) {
+        // Short timeout so a stop request is noticed well within one interval
+        let (number, snapshot) = match sampler.wait_next(seen, Duration::from_millis(200)) {
+            Some(next) => next,
+            None => continue,
+        };
+        seen = number;
+        // Lines are flushed one by one so a crash still leaves every sample readable
+        write_line(&mut out, &snapshot)?;
+        samples += 1;
+        #[cfg(feature = "sqlite")]
+        if let Some((db, id)) = &store {
+            if let Err(e) = db.insert_sample(Some(*id), &snapshot) {
+                eprintln!("[Record] Stopped writing to the telemetry store: {}", e);
+                store = None;
+            }
+        }
+
+        if info.game.is_none() && game.is_none() && last_scan.elapsed() >= GAME_RESCAN {
+            game = detect_running_game();
+            last_scan = Instant::now();
+        }
+    }
+
+    let end = SessionEnd {
+        ended_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        samples,
+        game,
+    };
+    write_line(&mut out, &Marker::End(&end))?;
+    #[cfg(feature = "sqlite")]
+    if let Some((db, id)) = &store {
+        if let Err(e) = db.end_session(*id, &end) {
+            eprintln!("[Record] Could not close the session in the telemetry store: {}", e);
+        }
+    }
+    enforce_default();
+    Ok((path, samples))
+}
+
+fn write_line<T: Serialize>(out: &mut BufWriter<File>, record: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+/// "Valve Jupiter" style model string from DMI.
+pub fn device_model() -> Option<String> {
+    let read = |name: &str| {
+        fs::read_to_string(format!("/sys/class/dmi/id/{}", name))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    match (read("sys_vendor"), read("product_name")) {
+        (Some(vendor), Some(product)) => Some(format!("{} {}", vendor, product)),
+        (vendor, product) => product.or(vendor),
+    }
+}
+
+pub fn hostname() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname").ok().map(|s| s.trim().to_string())
+}
@@ -1,59 +1,347 @@
-use std::{fs::File, io::{BufRead, BufReader}, thread, time::Duration};
-use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
-use crate::hardware::get_gpu_info;
+use crate::hardware::{backend_for, collect_all_gpu_stats, enumerate_gpus};
+use crate::memory::BYTES_PER_GB;
+use crate::power::format_minutes;
+use crate::pressure::PressureLine;
+use crate::processes::{read_pss_bytes, sort_processes, ProcessSort};
+use crate::output::{render, OutputFormat};
+use crate::sampler;
+use crate::sensors::{enumerate_sensors, SensorKind, SensorReading};
 
 pub fn print_system_status() {
     println!("=== Starting System Status Report ===");
-    // Initialize sysinfo
-    let refresh = RefreshKind::new()
-        .with_cpu(CpuRefreshKind::everything())
-        .with_memory(MemoryRefreshKind::new());
-
-    let mut sys = System::new_with_specifics(refresh);
-    sys.refresh_cpu();
-    thread::sleep(Duration::from_millis(1000));
-    sys.refresh_cpu();
-    sys.refresh_memory();
+    // The shared sampler already holds the previous counters, so this doesn't sleep
+    let snapshot = sampler::shared().wait_latest();
 
     println!("\n=== System Status ===");
 
     // Accurate Total CPU from /proc/stat
-    if let Some(cpu_usage) = calculate_proc_cpu_usage() {
+    if let Some(cpu_usage) = snapshot.accurate_cpu_total {
         println!("Accurate Total CPU (from /proc/stat): {:.1}%", cpu_usage);
     } else {
         println!("Accurate Total CPU: [error]");
     }
 
     // Uptime
-    println!("Uptime: {} seconds", System::uptime());
+    println!("Uptime: {} seconds", snapshot.uptime);
 
     // Memory
-    let total_memory = sys.total_memory();
-    let used_memory = sys.used_memory();
     println!(
         "Memory: {:.2} / {:.2} GB",
-        used_memory as f64 / 1_048_576.0,
-        total_memory as f64 / 1_048_576.0
+        snapshot.memory_used_gb,
+        snapshot.memory_total_gb
     );
+    if let Some(m) = &snapshot.memory {
+        println!(
+            "  available {:.2} GB  cached {:.2} GB  buffers {:.2} GB  shmem {:.2} GB  dirty {:.1} MB",
+            gb(m.available_bytes), gb(m.cached_bytes), gb(m.buffers_bytes), gb(m.shmem_bytes),
+            m.dirty_bytes as f64 / 1_000_000.0
+        );
+        println!(
+            "Swap: {:.2} / {:.2} GB  (swap cache {:.2} GB)",
+            gb(m.swap_used_bytes()), gb(m.swap_total_bytes), gb(m.swap_cached_bytes)
+        );
+    }
+    for z in &snapshot.zram {
+        println!(
+            "{}: {:.2} GB stored in {:.2} GB RAM (disksize {:.2} GB){}{}",
+            z.name,
+            gb(z.orig_data_bytes),
+            gb(z.mem_used_bytes),
+            gb(z.disksize_bytes),
+            z.compression_ratio.map_or(String::new(), |r| format!("  ratio {:.2}x", r)),
+            z.algorithm.as_ref().map_or(String::new(), |a| format!("  [{}]", a)),
+        );
+    }
+
+    // CPU: sysinfo total for comparison, then the /proc/stat breakdown
+    if let Some(total) = snapshot.sysinfo_cpu_total {
+        println!("Sysinfo Reported Total CPU: {:>5.1}%", total);
+    }
 
-    // CPU: sysinfo total + per-core
-    let cpus = sys.cpus();
-    if let Some(total) = cpus.first() {
-        println!("Sysinfo Reported Total CPU: {:>5.1}%", total.cpu_usage());
+    if let Some(b) = &snapshot.cpu_breakdown {
+        println!(
+            "Breakdown: user {:.1}%  nice {:.1}%  system {:.1}%  iowait {:.1}%  irq {:.1}%  softirq {:.1}%  steal {:.1}%  guest {:.1}%",
+            b.user_percent, b.nice_percent, b.system_percent, b.iowait_percent,
+            b.irq_percent, b.softirq_percent, b.steal_percent, b.guest_percent
+        );
     }
 
-    for (i, cpu) in cpus.iter().skip(1).enumerate() {
-        println!("Core {:2}: {:>5.1}%", i, cpu.cpu_usage());
+    println!("{:<8} {:>6} {:>6} {:>6} {:>6} {:>6}", "", "busy", "user", "sys", "iowait", "irq");
+    for (n, core) in &snapshot.per_core_breakdown {
+        println!(
+            "Core {:2}: {:>5.1}% {:>5.1}% {:>5.1}% {:>5.1}% {:>5.1}%",
+            n,
+            core.busy_percent,
+            core.user_percent,
+            core.system_percent,
+            core.iowait_percent,
+            core.irq_percent + core.softirq_percent
+        );
+    }
+
+    // CPU clocks per cpufreq policy
+    println!("\n--- CPU Frequency ---");
+    for p in &snapshot.cpu_policies {
+        let mut line = format!(
+            "{:<9} cpus [{}]: {} MHz",
+            p.policy,
+            p.cpus,
+            p.cur_freq_mhz.map_or("N/A".to_string(), |v| v.to_string())
+        );
+        if let (Some(min), Some(max)) = (p.min_freq_mhz, p.max_freq_mhz) {
+            line.push_str(&format!("  (limits {}-{} MHz)", min, max));
+        }
+        if let Some(gov) = &p.governor {
+            line.push_str(&format!("  governor {}", gov));
+        }
+        if let Some(epp) = &p.epp {
+            line.push_str(&format!("  epp {}", epp));
+        }
+        println!("{}", line);
     }
 
-    // GPU info 
-    let (gpu_util, gpu_temp, gpu_core_clk, gpu_mem_clk) = get_gpu_info();
+    let t = &snapshot.throttle;
+    println!(
+        "Throttling: {}  (core events {}, package events {}, GPU status {})",
+        if t.throttled { "YES" } else { "no" },
+        t.core_throttle_count.map_or("N/A".to_string(), |v| v.to_string()),
+        t.package_throttle_count.map_or("N/A".to_string(), |v| v.to_string()),
+        t.gpu_throttle_status.map_or("N/A".to_string(), |v| format!("{:#010x}", v)),
+    );
+
+    // Thermal zones with their trip points and cooling devices
+    println!("\n--- Thermal Zones ---");
+    if snapshot.thermal_zones.is_empty() {
+        println!("No thermal zones found.");
+    }
+    for zone in &snapshot.thermal_zones {
+        println!(
+            "{} ({}): {}",
+            zone.name,
+            zone.zone_type,
+            zone.temp_c.map_or("N/A".to_string(), |t| format!("{:.1}°C", t))
+        );
+        if let Some(trip) = zone.near_trip() {
+            println!(
+                "  [!] Near {} trip {} at {:.1}°C",
+                trip.kind, trip.index, trip.temp_c
+            );
+        }
+        for trip in &zone.trips {
+            println!(
+                "  trip {}: {:<8} {:.1}°C{}",
+                trip.index,
+                trip.kind,
+                trip.temp_c,
+                trip.hysteresis_c.filter(|h| *h > 0.0).map_or(String::new(), |h| format!("  (hyst {:.1}°C)", h))
+            );
+        }
+        for cdev in &zone.cooling_devices {
+            println!(
+                "  {} ({}): state {}/{}{}",
+                cdev.name,
+                cdev.device_type,
+                cdev.cur_state.map_or("N/A".to_string(), |v| v.to_string()),
+                cdev.max_state.map_or("N/A".to_string(), |v| v.to_string()),
+                cdev.trip.map_or(String::new(), |t| format!("  (trip {})", t))
+            );
+        }
+    }
+
+    // Storage throughput per disk, with the mounts and Steam libraries on it
+    println!("\n--- Storage ---");
+    if snapshot.disks.is_empty() {
+        println!("No disk statistics available.");
+    } else {
+        println!(
+            "{:<10} {:>9} {:>10} {:>7} {:>7} {:>6} {:>7}",
+            "Device", "Read MB/s", "Write MB/s", "r/s", "w/s", "util", "temp"
+        );
+    }
+    for d in &snapshot.disks {
+        println!(
+            "{:<10} {:>9.2} {:>10.2} {:>7.0} {:>7.0} {:>5.1}% {:>7}",
+            d.name,
+            d.read_bytes_per_sec / 1_000_000.0,
+            d.write_bytes_per_sec / 1_000_000.0,
+            d.read_iops,
+            d.write_iops,
+            d.util_percent,
+            d.temp_c.map_or("N/A".to_string(), |t| format!("{:.0}°C", t)),
+        );
+        if let Some(model) = &d.model {
+            println!("  Model: {}{}", model, if d.removable { " (removable)" } else { "" });
+        }
+        if !d.mounts.is_empty() {
+            println!("  Mounts: {}", d.mounts.join(", "));
+        }
+        for lib in &d.steam_libraries {
+            println!("  Steam library: {}", lib);
+        }
+    }
+
+    // Network throughput per interface
+    println!("\n--- Network ---");
+    if snapshot.network.is_empty() {
+        println!("No network interfaces found.");
+    }
+    for n in &snapshot.network {
+        println!(
+            "{:<10} rx {:>8.1} KB/s  tx {:>8.1} KB/s  ({:.0}/{:.0} pkt/s)  {}{}",
+            n.name,
+            n.rx_bytes_per_sec / 1000.0,
+            n.tx_bytes_per_sec / 1000.0,
+            n.rx_packets_per_sec,
+            n.tx_packets_per_sec,
+            n.operstate.as_deref().unwrap_or("unknown"),
+            n.link_speed_mbps.map_or(String::new(), |s| format!("  {} Mb/s", s)),
+        );
+        if n.rx_errors + n.tx_errors + n.rx_dropped + n.tx_dropped > 0 {
+            println!(
+                "  errors rx {} tx {}  dropped rx {} tx {}",
+                n.rx_errors, n.tx_errors, n.rx_dropped, n.tx_dropped
+            );
+        }
+        if let Some(w) = &n.wifi {
+            println!(
+                "  Wi-Fi: quality {}  signal {}  noise {}",
+                w.link_quality.map_or("N/A".to_string(), |v| format!("{:.0}/70", v)),
+                w.signal_dbm.map_or("N/A".to_string(), |v| format!("{:.0} dBm", v)),
+                w.noise_dbm.map_or("N/A".to_string(), |v| format!("{:.0} dBm", v)),
+            );
+        }
+    }
+
+    // Battery / AC
+    println!("\n--- Power Supply ---");
+    let power = &snapshot.power;
+    println!(
+        "AC adapter: {}",
+        match power.ac_online {
+            Some(true) => "online",
+            Some(false) => "offline",
+            None => "N/A",
+        }
+    );
+    if power.batteries.is_empty() {
+        println!("No battery found.");
+    }
+    for b in &power.batteries {
+        println!(
+            "{}: {}%  {}",
+            b.name,
+            b.capacity_percent.map_or("N/A".to_string(), |v| v.to_string()),
+            b.status.as_deref().unwrap_or("Unknown")
+        );
+        if let (Some(now), Some(full)) = (b.energy_now_wh, b.energy_full_wh) {
+            println!(
+                "  Energy: {:.2} / {:.2} Wh  (design {}, health {})",
+                now,
+                full,
+                b.energy_full_design_wh.map_or("N/A".to_string(), |v| format!("{:.2} Wh", v)),
+                b.health_percent.map_or("N/A".to_string(), |v| format!("{:.1}%", v)),
+            );
+        }
+        println!(
+            "  Draw: {}  (avg {})  {}  {}",
+            b.power_w.map_or("N/A".to_string(), |v| format!("{:.2} W", v)),
+            b.avg_power_w.map_or("N/A".to_string(), |v| format!("{:.2} W", v)),
+            b.voltage_v.map_or("N/A".to_string(), |v| format!("{:.2} V", v)),
+            b.current_a.map_or("N/A".to_string(), |v| format!("{:.2} A", v)),
+        );
+        if let Some(cycles) = b.cycle_count {
+            println!("  Cycles: {}", cycles);
+        }
+        if let Some(end) = b.charge_end_threshold {
+            println!(
+                "  Charge limit: {}%{}",
+                end,
+                b.charge_start_threshold.map_or(String::new(), |s| format!("  (resume below {}%)", s))
+            );
+        }
+        if let Some(min) = b.time_to_empty_min {
+            println!("  Time to empty: {}", format_minutes(min));
+        }
+        if let Some(min) = b.time_to_full_min {
+            println!("  Time to full: {}", format_minutes(min));
+        }
+    }
+
+    // Contention: load average and Pressure Stall Information
+    println!("\n--- Load & Pressure ---");
+    match &snapshot.load_average {
+        Some(l) => println!(
+            "Load average: {:.2} {:.2} {:.2}  ({} running / {} tasks)",
+            l.load1, l.load5, l.load15, l.running_tasks, l.total_tasks
+        ),
+        None => println!("Load average: N/A"),
+    }
+    let psi = &snapshot.pressure;
+    for (name, pressure) in [("CPU", &psi.cpu), ("Memory", &psi.memory), ("IO", &psi.io)] {
+        match pressure {
+            Some(p) => println!(
+                "{:<7} some {}  full {}",
+                name,
+                format_pressure_line(p.some.as_ref()),
+                format_pressure_line(p.full.as_ref())
+            ),
+            None => println!("{:<7} PSI not available", name),
+        }
+    }
+
+    // GPU info
+    let stats = &snapshot.gpu;
+    let (gpu_util, gpu_temp, gpu_core_clk, gpu_mem_clk) = (
+        snapshot.gpu_util_percent,
+        snapshot.gpu_temp_celsius,
+        snapshot.gpu_core_clock_mhz,
+        snapshot.gpu_mem_clock_mhz,
+    );
 
     println!("\n--- GPU Info ---");
+    match &snapshot.gpu_card {
+        Some(card) => {
+            println!("GPU Card:           {}", card.describe());
+            println!("GPU Backend:        {}", backend_for(card).name());
+        }
+        None => println!("GPU Card:           N/A"),
+    }
     println!("GPU Load:           {}%", gpu_util.map_or("N/A".to_string(), |v| v.to_string()));
     println!("GPU Temperature:    {}°C", gpu_temp.map_or("N/A".to_string(), |v| format!("{:.1}", v)));
     println!("GPU Core Clock:     {} MHz", gpu_core_clk.map_or("N/A".to_string(), |v| v.to_string()));
     println!("GPU Memory Clock:   {} MHz", gpu_mem_clk.map_or("N/A".to_string(), |v| v.to_string()));
+    println!("GPU Power:          {} W", stats.power_watts.map_or("N/A".to_string(), |v| format!("{:.2}", v)));
+    if stats.min_freq_mhz.is_some() || stats.max_freq_mhz.is_some() {
+        println!(
+            "GPU Freq Limits:    {} - {} MHz (boost {})",
+            stats.min_freq_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+            stats.max_freq_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+            stats.boost_freq_mhz.map_or("N/A".to_string(), |v| format!("{} MHz", v)),
+        );
+    }
+    if let Some(rc6) = stats.rc6_residency_percent {
+        println!("GPU RC6 Residency:  {:.1}%", rc6);
+    }
+
+    // Per-process GPU usage from DRM fdinfo
+    let gpu_procs = &snapshot.gpu_processes;
+    println!("\n--- GPU Processes ---");
+    if gpu_procs.is_empty() {
+        println!("No processes with open render nodes (or fdinfo not readable).");
+    } else {
+        println!("{:>7}  {:<16} {:>6} {:>8} {:>10} {:>10}", "PID", "NAME", "GFX%", "COMPUTE%", "VRAM MiB", "GTT MiB");
+        for p in gpu_procs.iter().take(10) {
+            println!(
+                "{:>7}  {:<16} {:>6.1} {:>8.1} {:>10.1} {:>10.1}",
+                p.pid,
+                p.name,
+                p.engine_percent("gfx"),
+                p.engine_percent("compute"),
+                p.vram_bytes as f64 / 1_048_576.0,
+                p.gtt_bytes as f64 / 1_048_576.0,
+            );
+        }
+    }
 
 
 
@@ -65,40 +353,116 @@ pub fn print_system_status() {
     io::stdout().flush().ok();
 }
 
-pub fn read_proc_stat() -> Option<(u64, u64)> {
-    let file = File::open("/proc/stat").ok()?;
-    let reader = BufReader::new(file);
+/// Prints the same snapshot the logger writes, as JSON, YAML or CSV, with nothing else on stdout.
+pub fn print_structured_status(format: OutputFormat) {
+    let snapshot = sampler::shared().wait_latest();
+    match render(&snapshot, format) {
+        Ok(text) => print!("{}", text),
+        Err(e) => eprintln!("[Status] Failed to render snapshot: {}", e),
+    }
+}
+
+/// Top-style table of the `count` busiest processes by the chosen sort order.
+pub fn print_top_processes(count: usize, sort: ProcessSort) {
+    let snapshot = sampler::shared().wait_latest();
+    let mut processes = snapshot.processes;
+    sort_processes(&mut processes, sort);
 
-    for line in reader.lines().flatten() {
-        if line.starts_with("cpu ") {
-            let parts: Vec<u64> = line
-                .split_whitespace()
-                .skip(1)
-                .filter_map(|s| s.parse().ok())
-                .collect();
+    println!("=== Top {} Processes by {} ===", count, sort.label());
+    println!(
+        "{:>7} {:<16} {:>1} {:>6} {:>9} {:>9} {:>4} {:>4} {:>5}  Cgroup",
+        "PID", "Name", "S", "CPU%", "RSS MiB", "PSS MiB", "Thr", "Nice", "GPU%"
+    );
+    for p in processes.iter().take(count) {
+        println!(
+            "{:>7} {:<16} {:>1} {:>6.1} {:>9.1} {:>9} {:>4} {:>4} {:>5.1}  {}",
+            p.pid,
+            p.name,
+            p.state,
+            p.cpu_percent,
+            p.rss_bytes as f64 / 1_048_576.0,
+            read_pss_bytes(p.pid).map_or("N/A".to_string(), |b| format!("{:.1}", b as f64 / 1_048_576.0)),
+            p.threads,
+            p.nice,
+            p.gpu_percent,
+            p.cgroup.as_deref().unwrap_or("-"),
+        );
+    }
+}
+
+pub fn print_gpu_list() {
+    let cards = enumerate_gpus();
+    if cards.is_empty() {
+        println!("[GPU] No DRM cards found.");
+        return;
+    }
+
+    println!("=== Detected GPUs ===");
+    for card in cards {
+        println!("{}", card.describe());
+        println!("    vendor: {}  device: {}", card.vendor_id, card.device_id);
+        println!("    driver: {}", card.driver.as_deref().unwrap_or("N/A"));
+    }
 
-            if parts.len() <5 {
-                return None;
-            }
-            let idle = parts[3] + parts[4];
-            let total: u64 = parts.iter().sum();
-            return Some((total, idle));
+    for (card, stats) in collect_all_gpu_stats() {
+        match stats {
+            Ok(s) => println!(
+                "[{}] ({}) load {}%  temp {}°C  core {} MHz  mem {} MHz",
+                card.index,
+                backend_for(&card).name(),
+                s.gpu_util_percent.map_or("N/A".to_string(), |v| v.to_string()),
+                s.temperature_c.map_or("N/A".to_string(), |v| format!("{:.1}", v)),
+                s.core_clock_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+                s.memory_clock_mhz.map_or("N/A".to_string(), |v| v.to_string()),
+            ),
+            Err(e) => println!("[{}] stats unavailable: {}", card.index, e),
         }
     }
-    None
 }
 
-pub fn calculate_proc_cpu_usage() -> Option<f64> {
-    let (total1, idle1) = read_proc_stat()?;
-    thread::sleep(Duration::from_millis(1000));
-    let (total2, idle2) = read_proc_stat()?;
+pub fn print_sensors() {
+    let chips = enumerate_sensors();
+    if chips.is_empty() {
+        println!("[Sensors] No hwmon devices found.");
+        return;
+    }
 
-    let delta_total = total2.saturating_sub(total1);
-    let delta_idle = idle2.saturating_sub(idle1);
+    println!("=== Sensors ===");
+    for chip in chips {
+        println!("\n--- {} ({}) ---", chip.name, chip.hwmon);
+        for reading in &chip.readings {
+            println!("{:<20} {}", format!("{}:", reading.display_name()), format_reading(reading));
+        }
+    }
+    println!("===============");
+}
+
+/// Formats a sensor value with its unit and any max/crit thresholds.
+pub fn format_reading(reading: &SensorReading) -> String {
+    let unit = reading.kind.unit();
+    let fmt = |v: f64| match reading.kind {
+        SensorKind::Fan => format!("{:.0} {}", v, unit),
+        _ => format!("{:.2} {}", v, unit),
+    };
 
-    if delta_total == 0 {
-        return Some(0.0);
+    let mut out = reading.value.map_or("N/A".to_string(), fmt);
+    if let Some(max) = reading.max {
+        out.push_str(&format!("  (max {})", fmt(max)));
     }
+    if let Some(crit) = reading.crit {
+        out.push_str(&format!("  (crit {})", fmt(crit)));
+    }
+    out
+}
 
-    Some(100.0 * (delta_total - delta_idle) as f64 / delta_total as f64)
-}
\ No newline at end of file
+/// "avg10/avg60/avg300 %" for one PSI line.
+fn format_pressure_line(line: Option<&PressureLine>) -> String {
+    match line {
+        Some(l) => format!("{:>5.2}/{:>5.2}/{:>5.2}%", l.avg10, l.avg60, l.avg300),
+        None => format!("{:>19}", "N/A"),
+    }
+}
+
+fn gb(bytes: u64) -> f64 {
+    bytes as f64 / BYTES_PER_GB
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::games::steam_library_paths;
+use crate::hardware::read_trimmed;
+
+/// /proc/diskstats always counts in 512-byte sectors, whatever the device's real sector size.
+const SECTOR_BYTES: f64 = 512.0;
+
+/// Cumulative counters for one device from /proc/diskstats.
+#[derive(Debug, Clone, Default)]
+pub struct DiskCounters {
+    pub name: String,
+    pub reads_completed: u64,
+    pub sectors_read: u64,
+    pub writes_completed: u64,
+    pub sectors_written: u64,
+    pub io_ticks_ms: u64, // Time the device had I/O in flight
+}
+
+/// One read of /proc/diskstats and when it was taken.
+#[derive(Debug, Clone)]
+pub struct DiskSample {
+    pub at: Instant,
+    pub disks: Vec<DiskCounters>,
+}
+
+/// Throughput of one whole disk between two samples, with its mounts and Steam libraries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub name: String, // e.g. "nvme0n1", "mmcblk0"
+    pub model: Option<String>,
+    pub removable: bool,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub util_percent: f64,
+    pub temp_c: Option<f64>,
+    pub mounts: Vec<String>,
+    pub steam_libraries: Vec<String>,
+}
+
+/// Reads the counters of every whole disk, skipping loop, ram and zram devices
+/// (zram is reported by the memory module).
+pub fn read_diskstats() -> Option<DiskSample> {
+    let content = fs::read_to_string("/proc/diskstats").ok()?;
+    let mut disks = Vec::new();
+
+    // major minor name reads merged sectors ms writes merged sectors ms in_flight io_ticks ...
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 13 {
+            continue;
+        }
+        let name = parts[2];
+        if !is_whole_disk(name) {
+            continue;
+        }
+        let field = |i: usize| parts[i].parse::<u64>().unwrap_or(0);
+        disks.push(DiskCounters {
+            name: name.to_string(),
+            reads_completed: field(3),
+            sectors_read: field(5),
+            writes_completed: field(7),
+            sectors_written: field(9),
+            io_ticks_ms: field(12),
+        });
+    }
+
+    Some(DiskSample { at: Instant::now(), disks })
+}
+
+fn is_whole_disk(name: &str) -> bool {
+    if ["loop", "ram", "zram"].iter().any(|p| name.starts_with(p)) {
+        return false;
+    }
+    // Partitions live under their disk in sysfs, so only whole disks have a /sys/block entry
+    Path::new("/sys/block").join(name).exists()
+}
+
+/// Per-disk rates between two samples. Disks missing from `prev` (just hotplugged) are skipped.
+pub fn disk_usage_between(prev: &DiskSample, curr: &DiskSample) -> Vec<DiskUsage> {
+    let secs = curr.at.duration_since(prev.at).as_secs_f64();
+    if secs <= 0.0 {
+        return Vec::new();
+    }
+    let mut cache = MOUNT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if matches!(&*cache, Some(c) if c.read_at.elapsed() >= MOUNT_REFRESH) {
+        *cache = None;
+    }
+    let MountCache { mounts, libraries, .. } = cache.get_or_insert_with(MountCache::read);
+
+    curr.disks
+        .iter()
+        .filter_map(|c| {
+            let p = prev.disks.iter().find(|p| p.name == c.name)?;
+            let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+            let sys = Path::new("/sys/block").join(&c.name);
+
+            let disk_mounts: Vec<&Mount> = mounts.iter().filter(|m| m.disk == c.name).collect();
+            let steam_libraries = libraries
+                .iter()
+                .filter(|(_, disk)| *disk == c.name)
+                .map(|(lib, _)| lib.clone())
+                .collect();
+
+            Some(DiskUsage {
+                name: c.name.clone(),
+                model: read_trimmed(&sys.join("device/model")).or_else(|| read_trimmed(&sys.join("device/name"))),
+                removable: read_trimmed(&sys.join("removable")).as_deref() == Some("1"),
+                read_bytes_per_sec: rate(c.sectors_read, p.sectors_read) * SECTOR_BYTES,
+                write_bytes_per_sec: rate(c.sectors_written, p.sectors_written) * SECTOR_BYTES,
+                read_iops: rate(c.reads_completed, p.reads_completed),
+                write_iops: rate(c.writes_completed, p.writes_completed),
+                util_percent: (100.0 * rate(c.io_ticks_ms, p.io_ticks_ms) / 1000.0).min(100.0),
+                temp_c: read_disk_temp(&sys),
+                mounts: disk_mounts.iter().map(|m| m.mount_point.display().to_string()).collect(),
+                steam_libraries,
+            })
+        })
+        .collect()
+}
+
+/// nvme, mmc and (with drivetemp) SATA disks register a hwmon device under the block device.
+fn read_disk_temp(sys: &Path) -> Option<f64> {
+    for parent in [sys.join("device"), sys.join("device/hwmon"), sys.join("device/device/hwmon")] {
+        let entries = match fs::read_dir(&parent) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().starts_with("hwmon") {
+                continue;
+            }
+            let milli = read_trimmed(&entry.path().join("temp1_input")).and_then(|v| v.parse::<f64>().ok());
+            if let Some(milli) = milli {
+                return Some(milli / 1000.0);
+            }
+        }
+    }
+    None
+}
+
+/// A mounted filesystem backed by a block device.
+struct Mount {
+    mount_point: PathBuf,
+    disk: String, // Whole disk the filesystem lives on
+}
+
+/// Mounts and Steam libraries rarely change, so they're re-read at most this often
+/// rather than re-parsing mountinfo and libraryfolders.vdf on every sample.
+const MOUNT_REFRESH: Duration = Duration::from_secs(30);
+
+static MOUNT_CACHE: Mutex<Option<MountCache>> = Mutex::new(None);
+
+struct MountCache {
+    read_at: Instant,
+    mounts: Vec<Mount>,
+    libraries: Vec<(String, String)>, // (Steam library path, disk it lives on)
+}
+
+impl MountCache {
+    fn read() -> MountCache {
+        let mounts = read_mounts();
+        let libraries = steam_library_paths()
+            .iter()
+            .filter_map(|lib| {
+                let disk = mount_for_path(&mounts, lib)?.disk.clone();
+                Some((lib.display().to_string(), disk))
+            })
+            .collect();
+        MountCache { read_at: Instant::now(), mounts, libraries }
+    }
+}
+
+fn read_mounts() -> Vec<Mount> {
+    let content = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    let mut mounts = Vec::new();
+
+    // id parent major:minor root mount_point options ... - fstype source super_options
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        let source = parts
+            .iter()
+            .position(|p| *p == "-")
+            .and_then(|i| parts.get(i + 2))
+            .copied()
+            .unwrap_or_default();
+
+        // btrfs reports an anonymous major:minor, so fall back to the /dev source path
+        let device = fs::canonicalize(format!("/sys/dev/block/{}", parts[2]))
+            .ok()
+            .or_else(|| {
+                let dev = fs::canonicalize(source).ok()?;
+                fs::canonicalize(Path::new("/sys/class/block").join(dev.file_name()?)).ok()
+            });
+        if let Some(disk) = device.as_deref().and_then(whole_disk_name) {
+            mounts.push(Mount {
+                mount_point: PathBuf::from(unescape_mount_path(parts[4])),
+                disk,
+            });
+        }
+    }
+    mounts
+}
+
+/// Maps a sysfs block device path to its whole disk: a partition's parent directory is the disk.
+fn whole_disk_name(sys_path: &Path) -> Option<String> {
+    let dir = if sys_path.join("partition").exists() { sys_path.parent()? } else { sys_path };
+    Some(dir.file_name()?.to_string_lossy().to_string())
+}
+
+/// The mount with the longest mount point that contains `path`.
+fn mount_for_path<'a>(mounts: &'a [Mount], path: &Path) -> Option<&'a Mount> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    mounts
+        .iter()
+        .filter(|m| path.starts_with(&m.mount_point))
+        .max_by_key(|m| m.mount_point.as_os_str().len())
+}
+
+/// mountinfo escapes spaces, tabs, newlines and backslashes as octal, e.g. "\040".
+fn unescape_mount_path(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            if let Some(code) = raw.get(i + 1..i + 4).and_then(|o| u8::from_str_radix(o, 8).ok()) {
+                out.push(code);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescapes_octal_mount_paths() {
+        assert_eq!(unescape_mount_path(r"/run/media/user/My\040Drive"), "/run/media/user/My Drive");
+        assert_eq!(unescape_mount_path(r"/mnt/a\011b\012c"), "/mnt/a\tb\nc");
+        assert_eq!(unescape_mount_path(r"/mnt/back\134slash"), r"/mnt/back\slash");
+        assert_eq!(unescape_mount_path("/mnt/plain"), "/mnt/plain");
+    }
+
+    #[test]
+    fn keeps_incomplete_or_invalid_escapes() {
+        assert_eq!(unescape_mount_path(r"/mnt/end\04"), r"/mnt/end\04");
+        assert_eq!(unescape_mount_path(r"/mnt/not\9octal"), r"/mnt/not\9octal");
+        assert_eq!(unescape_mount_path(r"/mnt/trailing\"), r"/mnt/trailing\");
+    }
+
+    #[test]
+    fn picks_the_longest_containing_mount() {
+        let mount = |point: &str, disk: &str| Mount { mount_point: PathBuf::from(point), disk: disk.to_string() };
+        let mounts = [
+            mount("/", "nvme0n1"),
+            mount("/nonexistent-games", "sda"),
+            mount("/nonexistent-games/My Drive", "sdb"),
+        ];
+
+        let disk_of = |path: &str| mount_for_path(&mounts, Path::new(path)).map(|m| m.disk.as_str());
+        assert_eq!(disk_of("/nonexistent-games/My Drive/SteamLibrary"), Some("sdb"));
+        assert_eq!(disk_of("/nonexistent-games/SteamLibrary"), Some("sda"));
+        // A shared name prefix isn't a containing directory
+        assert_eq!(disk_of("/nonexistent-games2/SteamLibrary"), Some("nvme0n1"));
+    }
+}
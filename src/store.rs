@@ -0,0 +1,391 @@
+use std::path::Path;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::paths::ensure_logs_dir;
+use crate::sampler::Snapshot;
+use crate::schema::SessionLog;
+use crate::session::{SessionEnd, SessionInfo};
+
+/// Database file name inside the logs directory, next to the JSON logs.
+pub const DB_FILE: &str = "telemetry.db";
+
+// The metric columns are copied out of the snapshot so aggregates don't have to parse
+// JSON; `data` keeps the whole record for reports that need everything else.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    id INTEGER PRIMARY KEY,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,
+    interval_ms INTEGER NOT NULL,
+    profile TEXT NOT NULL,
+    game TEXT,
+    app_id INTEGER,
+    device_model TEXT,
+    hostname TEXT,
+    schema_version INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS samples (
+    id INTEGER PRIMARY KEY,
+    session_id INTEGER REFERENCES sessions(id) ON DELETE CASCADE,
+    timestamp INTEGER NOT NULL,
+    cpu_percent REAL,
+    gpu_percent REAL,
+    gpu_temp_c REAL,
+    memory_used_gb REAL,
+    battery_percent REAL,
+    battery_power_w REAL,
+    throttled INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS samples_by_time ON samples(timestamp);
+CREATE INDEX IF NOT EXISTS samples_by_session ON samples(session_id, timestamp);
+";
+
+/// Per-sample values the store can aggregate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    CpuPercent,
+    GpuPercent,
+    GpuTemp,
+    MemoryUsed,
+    BatteryPercent,
+    BatteryPower,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 6] = [
+        Metric::CpuPercent,
+        Metric::GpuPercent,
+        Metric::GpuTemp,
+        Metric::MemoryUsed,
+        Metric::BatteryPercent,
+        Metric::BatteryPower,
+    ];
+
+    fn column(&self) -> &'static str {
+        match self {
+            Metric::CpuPercent => "cpu_percent",
+            Metric::GpuPercent => "gpu_percent",
+            Metric::GpuTemp => "gpu_temp_c",
+            Metric::MemoryUsed => "memory_used_gb",
+            Metric::BatteryPercent => "battery_percent",
+            Metric::BatteryPower => "battery_power_w",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::CpuPercent => "CPU %",
+            Metric::GpuPercent => "GPU %",
+            Metric::GpuTemp => "GPU °C",
+            Metric::MemoryUsed => "RAM GB",
+            Metric::BatteryPercent => "Battery %",
+            Metric::BatteryPower => "Battery W",
+        }
+    }
+}
+
+/// Which samples an aggregate or query covers. Window bounds are inclusive Unix seconds.
+#[derive(Debug, Clone, Copy)]
+pub enum Scope {
+    Session(i64),
+    Window { from: u64, to: u64 },
+}
+
+impl Scope {
+    fn condition(&self) -> &'static str {
+        match self {
+            Scope::Session(_) => "session_id = ?1",
+            Scope::Window { .. } => "timestamp BETWEEN ?1 AND ?2",
+        }
+    }
+
+    // Only as many values as `condition` has placeholders; SQLite rejects extras
+    fn params(&self) -> Vec<i64> {
+        match *self {
+            Scope::Session(id) => vec![id],
+            Scope::Window { from, to } => vec![from as i64, to.min(i64::MAX as u64) as i64],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Aggregate {
+    pub samples: u64,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p95: f64,
+}
+
+/// One row of the sessions table plus its sample count.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRow {
+    pub id: i64,
+    pub started_at: u64,
+    pub ended_at: Option<u64>, // None while recording, or if the recorder was killed
+    pub interval_ms: u64,
+    pub profile: String,
+    pub game: Option<String>,
+    pub app_id: Option<u32>,
+    pub device_model: Option<String>,
+    pub hostname: Option<String>,
+    pub samples: u64,
+}
+
+/// A snapshot as stored, with the session it belongs to.
+#[derive(Debug, Clone)]
+pub struct StoredSample {
+    pub session_id: Option<i64>, // None for one-off `--log` snapshots
+    pub timestamp: u64,
+    pub data: Value,
+}
+
+/// Every metric's aggregate for one scope, in `Metric::ALL` order.
+pub type Summary = Vec<(Metric, Option<Aggregate>)>;
+
+pub struct TelemetryStore {
+    conn: Connection,
+}
+
+impl TelemetryStore {
+    /// Opens the default database, creating it and its tables on first use.
+    pub fn open_default() -> rusqlite::Result<TelemetryStore> {
+        let dir = ensure_logs_dir().map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        TelemetryStore::open(&dir.join(DB_FILE))
+    }
+
+    pub fn open(path: &Path) -> rusqlite::Result<TelemetryStore> {
+        let conn = Connection::open(path)?;
+        // WAL lets the GUI read while a recording is writing
+        conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+        conn.busy_timeout(std::time::Duration::from_secs(2))?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(TelemetryStore { conn })
+    }
+
+    pub fn begin_session(&self, info: &SessionInfo) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO sessions (started_at, interval_ms, profile, game, app_id, device_model, hostname, schema_version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                info.started_at,
+                info.interval_ms,
+                info.profile,
+                info.game.as_ref().map(|g| &g.name),
+                info.game.as_ref().and_then(|g| g.app_id),
+                info.device_model,
+                info.hostname,
+                info.schema_version,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Marks a session finished, filling in the game if it was only found mid-session.
+    pub fn end_session(&self, id: i64, end: &SessionEnd) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE sessions SET ended_at = ?2,
+                 game = COALESCE(game, ?3), app_id = COALESCE(app_id, ?4)
+             WHERE id = ?1",
+            params![
+                id,
+                end.ended_at,
+                end.game.as_ref().map(|g| &g.name),
+                end.game.as_ref().and_then(|g| g.app_id),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_sample(&self, session_id: Option<i64>, snapshot: &Snapshot) -> rusqlite::Result<i64> {
+        let data = serde_json::to_string(snapshot).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        let battery = snapshot.power.batteries.first();
+        self.conn.execute(
+            "INSERT INTO samples (session_id, timestamp, cpu_percent, gpu_percent, gpu_temp_c, memory_used_gb,
+                                  battery_percent, battery_power_w, throttled, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                session_id,
+                snapshot.timestamp,
+                snapshot.accurate_cpu_total.or(snapshot.sysinfo_cpu_total.map(f64::from)),
+                snapshot.gpu_util_percent,
+                snapshot.gpu_temp_celsius,
+                snapshot.memory_used_gb,
+                battery.and_then(|b| b.capacity_percent),
+                battery.and_then(|b| b.power_w),
+                snapshot.throttle.throttled,
+                data,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Adds a session read back from its file. Returns None if a session with the same
+    /// start time is already stored, e.g. because it was recorded with the store enabled.
+    pub fn import_session(&self, info: &SessionInfo, log: &SessionLog) -> rusqlite::Result<Option<i64>> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE started_at = ?1)",
+            [info.started_at],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Ok(None);
+        }
+
+        // One transaction instead of one per sample keeps long sessions fast to import
+        let tx = self.conn.unchecked_transaction()?;
+        let id = self.begin_session(info)?;
+        for snapshot in &log.samples {
+            self.insert_sample(Some(id), snapshot)?;
+        }
+        if let Some(end) = &log.end {
+            self.end_session(id, end)?;
+        }
+        tx.commit()?;
+        Ok(Some(id))
+    }
+
+    /// Adds a one-off snapshot unless one with the same timestamp is already stored.
+    pub fn import_snapshot(&self, snapshot: &Snapshot) -> rusqlite::Result<bool> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM samples WHERE session_id IS NULL AND timestamp = ?1)",
+            [snapshot.timestamp],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            self.insert_sample(None, snapshot)?;
+        }
+        Ok(!exists)
+    }
+
+    /// Newest sessions first.
+    pub fn sessions(&self) -> rusqlite::Result<Vec<SessionRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id, s.started_at, s.ended_at, s.interval_ms, s.profile, s.game, s.app_id,
+                    s.device_model, s.hostname, (SELECT COUNT(*) FROM samples WHERE session_id = s.id)
+             FROM sessions s ORDER BY s.started_at DESC",
+        )?;
+        let rows = stmt.query_map([], session_row)?;
+        rows.collect()
+    }
+
+    pub fn session(&self, id: i64) -> rusqlite::Result<Option<SessionRow>> {
+        self.conn
+            .query_row(
+                "SELECT s.id, s.started_at, s.ended_at, s.interval_ms, s.profile, s.game, s.app_id,
+                        s.device_model, s.hostname, (SELECT COUNT(*) FROM samples WHERE session_id = s.id)
+                 FROM sessions s WHERE s.id = ?1",
+                [id],
+                session_row,
+            )
+            .optional()
+    }
+
+    /// Samples in time order. Uses the session or timestamp index depending on the scope.
+    pub fn samples(&self, scope: Scope) -> rusqlite::Result<Vec<StoredSample>> {
+        let sql = format!(
+            "SELECT session_id, timestamp, data FROM samples WHERE {} ORDER BY timestamp, id",
+            scope.condition()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(scope.params()), |row| {
+            let data: String = row.get(2)?;
+            Ok(StoredSample {
+                session_id: row.get(0)?,
+                timestamp: row.get(1)?,
+                data: parse_data(2, &data)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The newest snapshot as stored, from a session or a one-off `--log`.
+    pub fn latest_sample(&self) -> rusqlite::Result<Option<Value>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM samples ORDER BY timestamp DESC, id DESC LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        data.map(|d| parse_data(0, &d)).transpose()
+    }
+
+    /// Average, minimum, maximum and 95th percentile of one metric. Samples where the
+    /// metric wasn't available are ignored; None if none had it.
+    pub fn aggregate(&self, scope: Scope, metric: Metric) -> rusqlite::Result<Option<Aggregate>> {
+        let column = metric.column();
+        let filter = format!("{} AND {} IS NOT NULL", scope.condition(), column);
+
+        let (samples, avg, min, max): (u64, Option<f64>, Option<f64>, Option<f64>) = self.conn.query_row(
+            &format!("SELECT COUNT(*), AVG({0}), MIN({0}), MAX({0}) FROM samples WHERE {1}", column, filter),
+            params_from_iter(scope.params()),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        let (avg, min, max) = match (avg, min, max) {
+            (Some(avg), Some(min), Some(max)) if samples > 0 => (avg, min, max),
+            _ => return Ok(None),
+        };
+
+        // Nearest-rank percentile: SQLite has no percentile function, so sort and step in
+        let rank = ((samples as f64) * 0.95).ceil() as u64;
+        let p95: f64 = self.conn.query_row(
+            &format!(
+                "SELECT {0} FROM samples WHERE {1} ORDER BY {0} LIMIT 1 OFFSET {2}",
+                column,
+                filter,
+                rank.saturating_sub(1)
+            ),
+            params_from_iter(scope.params()),
+            |row| row.get(0),
+        )?;
+
+        Ok(Some(Aggregate { samples, avg, min, max, p95 }))
+    }
+
+    /// Samples older than `cutoff`, including every sample of sessions that started before it.
+    pub fn count_before(&self, cutoff: u64) -> rusqlite::Result<usize> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM samples
+             WHERE timestamp < ?1 OR session_id IN (SELECT id FROM sessions WHERE started_at < ?1)",
+            [cutoff],
+            |row| row.get(0),
+        )
+    }
+
+    /// Deletes what `count_before` counts, and the sessions themselves. Returns the samples removed.
+    pub fn delete_before(&self, cutoff: u64) -> rusqlite::Result<usize> {
+        let samples = self.conn.execute(
+            "DELETE FROM samples
+             WHERE timestamp < ?1 OR session_id IN (SELECT id FROM sessions WHERE started_at < ?1)",
+            [cutoff],
+        )?;
+        self.conn.execute("DELETE FROM sessions WHERE started_at < ?1", [cutoff])?;
+        Ok(samples)
+    }
+
+    pub fn summary(&self, scope: Scope) -> rusqlite::Result<Summary> {
+        Metric::ALL.iter().map(|&m| Ok((m, self.aggregate(scope, m)?))).collect()
+    }
+}
+
+fn session_row(row: &Row) -> rusqlite::Result<SessionRow> {
+    Ok(SessionRow {
+        id: row.get(0)?,
+        started_at: row.get(1)?,
+        ended_at: row.get(2)?,
+        interval_ms: row.get(3)?,
+        profile: row.get(4)?,
+        game: row.get(5)?,
+        app_id: row.get(6)?,
+        device_model: row.get(7)?,
+        hostname: row.get(8)?,
+        samples: row.get(9)?,
+    })
+}
+
+fn parse_data(column: usize, data: &str) -> rusqlite::Result<Value> {
+    serde_json::from_str(data)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, e.into()))
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+use serde::Serialize;
+
+use crate::sampler::Snapshot;
+
+/// Headline numbers for one session or time window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionSummary {
+    pub samples: usize,
+    pub duration_secs: f64,
+    pub cpu_avg_percent: Option<f64>,
+    pub cpu_p95_percent: Option<f64>,
+    pub gpu_avg_percent: Option<f64>,
+    pub gpu_p95_percent: Option<f64>,
+    pub package_power_avg_w: Option<f64>,
+    pub gpu_power_avg_w: Option<f64>,
+    pub peak_temp_c: Option<f64>,                    // Hottest GPU or thermal zone reading
+    pub battery_drain_percent_per_hour: Option<f64>, // Over discharging stretches only
+    pub battery_drain_w: Option<f64>,                // Same stretches, from energy_now
+    pub throttled_secs: f64,
+    pub throttled_percent: f64,
+}
+
+/// One line of the summary table, in display order.
+pub struct SummaryRow {
+    pub label: &'static str,
+    pub value: Option<f64>,
+    // Only `logs compare`, which needs the store, gives verdicts
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    pub rated: bool, // Lower is better; load isn't rated, since less of it can mean the game ran worse
+}
+
+impl SessionSummary {
+    pub fn duration_minutes(&self) -> f64 {
+        self.duration_secs / 60.0
+    }
+
+    /// The metrics. Duration isn't one of them, so it's shown separately.
+    pub fn rows(&self) -> Vec<SummaryRow> {
+        let load = |label, value| SummaryRow { label, value, rated: false };
+        let cost = |label, value| SummaryRow { label, value, rated: true };
+        vec![
+            load("CPU avg %", self.cpu_avg_percent),
+            load("CPU p95 %", self.cpu_p95_percent),
+            load("GPU avg %", self.gpu_avg_percent),
+            load("GPU p95 %", self.gpu_p95_percent),
+            cost("Package power W", self.package_power_avg_w),
+            cost("GPU power W", self.gpu_power_avg_w),
+            cost("Peak temp °C", self.peak_temp_c),
+            cost("Battery drain %/h", self.battery_drain_percent_per_hour),
+            cost("Battery drain W", self.battery_drain_w),
+            cost("Throttled (min)", Some(self.throttled_secs / 60.0)),
+            cost("Throttled %", Some(self.throttled_percent)),
+        ]
+    }
+}
+
+/// Prints the summary table, one metric per line.
+pub fn print_summary(summary: &SessionSummary) {
+    println!("  {:<18} {:>9}", "Duration (min)", format_value(Some(summary.duration_minutes())));
+    for row in summary.rows() {
+        println!("  {:<18} {:>9}", row.label, format_value(row.value));
+    }
+}
+
+pub fn format_value(value: Option<f64>) -> String {
+    value.map_or("N/A".to_string(), |v| format!("{:.1}", v))
+}
+
+/// Summarizes samples taken every `interval`, oldest first.
+pub fn summarize(samples: &[Snapshot], interval: Duration) -> SessionSummary {
+    let (first, last) = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return SessionSummary::default(),
+    };
+    let interval_secs = interval.as_secs_f64();
+
+    let cpu: Vec<f64> = samples
+        .iter()
+        .filter_map(|s| s.accurate_cpu_total.or(s.sysinfo_cpu_total.map(f64::from)))
+        .collect();
+    let gpu: Vec<f64> = samples.iter().filter_map(|s| s.gpu_util_percent.map(f64::from)).collect();
+    let package: Vec<f64> = samples.iter().filter_map(|s| s.package_power_watts).collect();
+    let gpu_power: Vec<f64> = samples.iter().filter_map(|s| s.gpu_power_watts.map(f64::from)).collect();
+
+    let peak_temp_c = samples
+        .iter()
+        .flat_map(|s| {
+            s.gpu_temp_celsius
+                .map(f64::from)
+                .into_iter()
+                .chain(s.thermal_zones.iter().filter_map(|z| z.temp_c))
+        })
+        .reduce(f64::max);
+
+    let throttled = samples.iter().filter(|s| s.throttle.throttled).count();
+    let (battery_drain_percent_per_hour, battery_drain_w) = battery_drain(samples);
+
+    SessionSummary {
+        samples: samples.len(),
+        // Each sample covers one interval, so n samples span n intervals
+        duration_secs: (Duration::from_secs(last.timestamp.saturating_sub(first.timestamp)) + interval).as_secs_f64(),
+        cpu_avg_percent: average(&cpu),
+        cpu_p95_percent: percentile(&cpu, 0.95),
+        gpu_avg_percent: average(&gpu),
+        gpu_p95_percent: percentile(&gpu, 0.95),
+        package_power_avg_w: average(&package),
+        gpu_power_avg_w: average(&gpu_power),
+        peak_temp_c,
+        battery_drain_percent_per_hour,
+        battery_drain_w,
+        throttled_secs: throttled as f64 * interval_secs,
+        throttled_percent: 100.0 * throttled as f64 / samples.len() as f64,
+    }
+}
+
+/// Drain of the first battery, counting only consecutive samples where it was discharging,
+/// so plugging in mid-session doesn't cancel out the drain before it.
+fn battery_drain(samples: &[Snapshot]) -> (Option<f64>, Option<f64>) {
+    let name = match samples.iter().find_map(|s| s.power.batteries.first()) {
+        Some(battery) => battery.name.clone(),
+        None => return (None, None),
+    };
+    let battery = |s: &Snapshot| s.power.batteries.iter().find(|b| b.name == name).cloned();
+
+    let (mut percent, mut wh, mut secs): (Option<f64>, Option<f64>, u64) = (None, None, 0);
+    for pair in samples.windows(2) {
+        let (a, b) = match (battery(&pair[0]), battery(&pair[1])) {
+            (Some(a), Some(b)) if b.is_discharging() => (a, b),
+            _ => continue,
+        };
+        secs += pair[1].timestamp.saturating_sub(pair[0].timestamp);
+        if let (Some(before), Some(after)) = (a.capacity_percent, b.capacity_percent) {
+            *percent.get_or_insert(0.0) += before as f64 - after as f64;
+        }
+        if let (Some(before), Some(after)) = (a.energy_now_wh, b.energy_now_wh) {
+            *wh.get_or_insert(0.0) += before - after;
+        }
+    }
+
+    if secs == 0 {
+        return (None, None);
+    }
+    let hours = secs as f64 / 3600.0;
+    (percent.map(|p| p / hours), wh.map(|w| w / hours))
+}
+
+/// Sampling interval of samples without session metadata: the median gap between timestamps.
+pub fn estimate_interval(samples: &[Snapshot]) -> Duration {
+    let mut gaps: Vec<u64> = samples
+        .windows(2)
+        .map(|pair| pair[1].timestamp.saturating_sub(pair[0].timestamp))
+        .collect();
+    gaps.sort_unstable();
+    // Timestamps are whole seconds, so sub-second intervals round to 1 s
+    Duration::from_secs(gaps.get(gaps.len() / 2).copied().unwrap_or(1).max(1))
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Nearest-rank percentile, the same definition the telemetry store uses.
+fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = ((sorted.len() as f64) * p).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(timestamps: &[u64]) -> Vec<Snapshot> {
+        timestamps.iter().map(|&timestamp| Snapshot { timestamp, ..Default::default() }).collect()
+    }
+
+    #[test]
+    fn duration_adds_a_sub_second_interval() {
+        let summary = summarize(&samples(&[100, 100, 101, 101, 102]), Duration::from_millis(500));
+        assert_eq!(summary.duration_secs, 2.5);
+        let summary = summarize(&samples(&[100]), Duration::from_millis(250));
+        assert_eq!(summary.duration_secs, 0.25);
+        let summary = summarize(&samples(&[100, 102]), Duration::from_secs(2));
+        assert_eq!(summary.duration_secs, 4.0);
+    }
+
+    #[test]
+    fn only_cost_rows_are_rated() {
+        let rows = SessionSummary::default().rows();
+        let rated: Vec<&str> = rows.iter().filter(|r| r.rated).map(|r| r.label).collect();
+        assert_eq!(
+            rated,
+            [
+                "Package power W",
+                "GPU power W",
+                "Peak temp °C",
+                "Battery drain %/h",
+                "Battery drain W",
+                "Throttled (min)",
+                "Throttled %",
+            ]
+        );
+    }
+}
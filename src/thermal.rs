@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::read_trimmed;
+
+/// A zone is flagged once its temperature is within this many °C of a passive, hot or critical trip.
+pub const NEAR_TRIP_MARGIN_C: f64 = 5.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TripPoint {
+    pub index: u32,
+    pub kind: String, // "active", "passive", "hot" or "critical"
+    pub temp_c: f64,
+    pub hysteresis_c: Option<f64>,
+}
+
+impl TripPoint {
+    /// Trips where the kernel starts throttling or shuts down, as opposed to just spinning a fan.
+    pub fn is_limiting(&self) -> bool {
+        matches!(self.kind.as_str(), "passive" | "hot" | "critical")
+    }
+}
+
+/// A cooling device bound to a zone through its cdevN link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoolingDevice {
+    pub name: String,        // e.g. "cooling_device3"
+    pub device_type: String, // e.g. "Processor", "Fan", "amdgpu"
+    pub cur_state: Option<u64>,
+    pub max_state: Option<u64>,
+    pub trip: Option<u32>,   // Trip point that activates it
+}
+
+/// One /sys/class/thermal/thermal_zoneN.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalZone {
+    pub name: String,      // e.g. "thermal_zone0"
+    pub zone_type: String, // e.g. "acpitz", "x86_pkg_temp"
+    pub temp_c: Option<f64>,
+    pub policy: Option<String>,
+    pub trips: Vec<TripPoint>,
+    pub cooling_devices: Vec<CoolingDevice>,
+}
+
+impl ThermalZone {
+    /// The lowest passive/hot/critical trip the zone is within `NEAR_TRIP_MARGIN_C` of (or past).
+    pub fn near_trip(&self) -> Option<&TripPoint> {
+        let temp = self.temp_c?;
+        self.trips
+            .iter()
+            .filter(|t| t.is_limiting() && t.temp_c > 0.0 && temp >= t.temp_c - NEAR_TRIP_MARGIN_C)
+            .min_by(|a, b| a.temp_c.total_cmp(&b.temp_c))
+    }
+}
+
+pub fn read_thermal_zones() -> Vec<ThermalZone> {
+    let mut zones = Vec::new();
+    let entries = match fs::read_dir("/sys/class/thermal") {
+        Ok(entries) => entries,
+        Err(_) => return zones,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with("thermal_zone") {
+            continue;
+        }
+        let dir = entry.path();
+        zones.push(ThermalZone {
+            zone_type: read_trimmed(&dir.join("type")).unwrap_or_else(|| name.clone()),
+            temp_c: read_millidegrees(&dir.join("temp")),
+            policy: read_trimmed(&dir.join("policy")),
+            trips: read_trips(&dir),
+            cooling_devices: read_bound_cooling_devices(&dir),
+            name,
+        });
+    }
+
+    zones.sort_by_key(|z| z.name.trim_start_matches("thermal_zone").parse::<u32>().unwrap_or(u32::MAX));
+    zones
+}
+
+fn read_trips(dir: &Path) -> Vec<TripPoint> {
+    let mut trips = Vec::new();
+    // trip_point_N_type / trip_point_N_temp / trip_point_N_hyst, numbered from 0 without gaps
+    for index in 0.. {
+        let prefix = format!("trip_point_{}", index);
+        let kind = match read_trimmed(&dir.join(format!("{}_type", prefix))) {
+            Some(kind) => kind,
+            None => break,
+        };
+        if let Some(temp_c) = read_millidegrees(&dir.join(format!("{}_temp", prefix))) {
+            trips.push(TripPoint {
+                index,
+                kind,
+                temp_c,
+                hysteresis_c: read_millidegrees(&dir.join(format!("{}_hyst", prefix))),
+            });
+        }
+    }
+    trips
+}
+
+fn read_bound_cooling_devices(dir: &Path) -> Vec<CoolingDevice> {
+    let mut devices = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return devices,
+    };
+
+    for entry in entries.flatten() {
+        let link = entry.file_name().to_string_lossy().to_string();
+        // cdevN is a symlink to ../cooling_deviceM; cdevN_trip_point sits next to it
+        let is_cdev = link
+            .strip_prefix("cdev")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        if !is_cdev {
+            continue;
+        }
+        let target = entry.path();
+        let name = fs::canonicalize(&target)
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| link.clone());
+        devices.push(CoolingDevice {
+            name,
+            device_type: read_trimmed(&target.join("type")).unwrap_or_default(),
+            cur_state: read_trimmed(&target.join("cur_state")).and_then(|v| v.parse().ok()),
+            max_state: read_trimmed(&target.join("max_state")).and_then(|v| v.parse().ok()),
+            trip: read_trimmed(&dir.join(format!("{}_trip_point", link))).and_then(|v| v.parse().ok()),
+        });
+    }
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn read_millidegrees(path: &Path) -> Option<f64> {
+    read_trimmed(path)?.parse::<i64>().ok().map(|v| v as f64 / 1000.0)
+}
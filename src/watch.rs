@@ -0,0 +1,143 @@
+use std::io::{self, Write};
+use std::thread;
+
+use crate::modes::active_profile;
+use crate::power::format_minutes;
+use crate::sampler::{self, Snapshot};
+
+/// Number of history points drawn in each sparkline.
+const SPARK_WIDTH: usize = 40;
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Redraws a compact dashboard in place every sampler interval until interrupted.
+pub fn run_watch() {
+    let sampler = sampler::shared();
+    sampler.wait_latest();
+
+    // Clear once, then only move the cursor home so frames overwrite each other without flicker
+    print!("\x1b[2J");
+    loop {
+        let history = sampler.history();
+        if let Some(latest) = history.last() {
+            let frame = render_dashboard(latest, &history, sampler.interval().as_millis());
+            let mut out = io::stdout().lock();
+            // "\x1b[K" clears what's left of a longer line from the previous frame, "\x1b[J" the rows below
+            let _ = write!(out, "\x1b[H");
+            for line in frame.lines() {
+                let _ = writeln!(out, "{}\x1b[K", line);
+            }
+            let _ = write!(out, "\x1b[J");
+            let _ = out.flush();
+        }
+        thread::sleep(sampler.interval());
+    }
+}
+
+fn render_dashboard(snap: &Snapshot, history: &[Snapshot], interval_ms: u128) -> String {
+    let mut lines = Vec::new();
+    let recent = &history[history.len().saturating_sub(SPARK_WIDTH)..];
+    let na = || "N/A".to_string();
+
+    lines.push(format!(
+        "Steam Deck Optimizer — watching every {} ms (Ctrl-C to quit)   uptime {}",
+        interval_ms,
+        format_minutes(snap.uptime as f64 / 60.0)
+    ));
+    let governor = snap.cpu_policies.first().and_then(|p| p.governor.as_deref());
+    lines.push(format!("Profile: {}", active_profile(governor)));
+    lines.push(String::new());
+
+    // CPU
+    lines.push(format!(
+        "CPU  {:>6}  {}  load {}",
+        snap.accurate_cpu_total.map_or_else(na, |v| format!("{:.1}%", v)),
+        sparkline(recent.iter().map(|s| s.accurate_cpu_total), 100.0),
+        snap.load_average.as_ref().map_or_else(na, |l| format!("{:.2}", l.load1)),
+    ));
+    let cores: Vec<String> = snap
+        .per_core
+        .iter()
+        .map(|v| format!("{}{:>3.0}", spark_char(*v as f64, 100.0), v))
+        .collect();
+    for chunk in cores.chunks(8) {
+        lines.push(format!("     {}", chunk.join(" ")));
+    }
+    let clocks: Vec<String> = snap
+        .cpu_policies
+        .iter()
+        .map(|p| p.cur_freq_mhz.map_or_else(na, |v| v.to_string()))
+        .collect();
+    lines.push(format!(
+        "     clocks {} MHz{}",
+        clocks.join("/"),
+        if snap.throttle.throttled { "   [THROTTLING]" } else { "" }
+    ));
+
+    // GPU
+    lines.push(format!(
+        "GPU  {:>6}  {}  {}  {}  {}",
+        snap.gpu_util_percent.map_or_else(na, |v| format!("{}%", v)),
+        sparkline(recent.iter().map(|s| s.gpu_util_percent.map(|v| v as f64)), 100.0),
+        snap.gpu_temp_celsius.map_or_else(na, |v| format!("{:.1}°C", v)),
+        snap.gpu.power_watts.map_or_else(na, |v| format!("{:.1} W", v)),
+        snap.gpu_core_clock_mhz.map_or_else(na, |v| format!("{} MHz", v)),
+    ));
+
+    // Memory
+    lines.push(format!(
+        "RAM  {:>5.0}%  {}  {:.2} / {:.2} GB",
+        percent(snap.memory_used_gb, snap.memory_total_gb),
+        sparkline(recent.iter().map(|s| Some(percent(s.memory_used_gb, s.memory_total_gb))), 100.0),
+        snap.memory_used_gb,
+        snap.memory_total_gb,
+    ));
+
+    // Battery
+    for b in &snap.power.batteries {
+        let estimate = match (b.time_to_empty_min, b.time_to_full_min) {
+            (Some(min), _) => format!("{} left", format_minutes(min)),
+            (None, Some(min)) => format!("{} to full", format_minutes(min)),
+            _ => String::new(),
+        };
+        let draws = recent.iter().map(|s| {
+            s.power.batteries.iter().find(|x| x.name == b.name).and_then(|x| x.power_w)
+        });
+        let peak = recent
+            .iter()
+            .flat_map(|s| s.power.batteries.iter().filter_map(|x| x.power_w))
+            .fold(1.0, f64::max);
+        lines.push(format!(
+            "BAT  {:>6}  {}  {}  {}  {}",
+            b.capacity_percent.map_or_else(na, |v| format!("{}%", v)),
+            sparkline(draws, peak),
+            b.status.as_deref().unwrap_or("Unknown"),
+            b.power_w.map_or_else(na, |v| format!("{:.1} W", v)),
+            estimate,
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn percent(used: f64, total: f64) -> f64 {
+    if total > 0.0 { 100.0 * used / total } else { 0.0 }
+}
+
+/// One block character per value, scaled against `max`; missing values are blank.
+fn sparkline(values: impl Iterator<Item = Option<f64>>, max: f64) -> String {
+    let mut line: String = values
+        .map(|v| v.map_or(' ', |v| spark_char(v, max)))
+        .collect();
+    // Pad on the left so the newest sample always sits at the same column
+    let len = line.chars().count();
+    if len < SPARK_WIDTH {
+        line = " ".repeat(SPARK_WIDTH - len) + &line;
+    }
+    line
+}
+
+fn spark_char(value: f64, max: f64) -> char {
+    let ratio = if max > 0.0 { (value / max).clamp(0.0, 1.0) } else { 0.0 };
+    SPARK_CHARS[(ratio * (SPARK_CHARS.len() - 1) as f64).round() as usize]
+}